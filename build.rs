@@ -35,6 +35,13 @@ const GMP_DIR: &'static str = "gmp-6.1.2-c";
 const MPFR_DIR: &'static str = "mpfr-4.0.2-p1-c";
 const MPC_DIR: &'static str = "mpc-1.1.0-c";
 
+// Cargo features that enable optional parts of MPFR, with the
+// corresponding configure options.
+const MPFR_FEATURES: &'static [(&'static str, &'static str)] = &[
+    ("float128", "--enable-float128"),
+    ("decimal-float", "--enable-decimal-float"),
+];
+
 #[derive(Clone, Copy, PartialEq)]
 enum Target {
     Mingw,
@@ -43,12 +50,12 @@ enum Target {
 }
 
 struct Environment {
-    rustc: OsString,
     out_dir: PathBuf,
     lib_dir: PathBuf,
     include_dir: PathBuf,
     build_dir: PathBuf,
     cache_dir: Option<PathBuf>,
+    mpfr_options: String,
     jobs: OsString,
     target: Target,
     make_check: bool,
@@ -67,8 +74,6 @@ enum Workaround47048 {
 }
 
 fn main() {
-    let rustc = cargo_env("RUSTC");
-
     let src_dir = PathBuf::from(cargo_env("CARGO_MANIFEST_DIR"));
     let out_dir = PathBuf::from(cargo_env("OUT_DIR"));
 
@@ -77,12 +82,43 @@ fn main() {
     let (version_prefix, version_patch) = get_version();
 
     println!("cargo:rerun-if-env-changed=GMP_MPFR_SYS_CACHE");
+    if rustc_later_eq(1, 80) {
+        // declare all the cfgs this script can emit
+        println!(
+            "cargo:rustc-check-cfg=cfg({})",
+            [
+                "newer_cache",
+                "nails",
+                "long_long_limb",
+                "gmp_6_2",
+                "gmp_6_3",
+                "mpfr_4_1",
+                "mpfr_4_2",
+                "mpc_1_2",
+                "mpc_1_3",
+            ]
+            .join(", ")
+        );
+    }
     let cache_dir = match env::var_os("GMP_MPFR_SYS_CACHE") {
         Some(ref c) if c.is_empty() => None,
         Some(c) => Some(PathBuf::from(c)),
         None => dirs::cache_dir().map(|c| c.join("gmp-mpfr-sys")),
     };
-    let cache_dir = cache_dir.map(|cache| cache.join(&version_prefix).join(target.clone()));
+    // Libraries built with optional MPFR features are cached
+    // separately, for example in x86_64-unknown-linux-gnu-float128.
+    let mut cache_target = target.clone();
+    let mut mpfr_options = String::new();
+    for &(feature, option) in MPFR_FEATURES {
+        let var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+        if there_is_env(&var) {
+            cache_target.push("-");
+            cache_target.push(feature);
+            mpfr_options.push_str(option);
+            mpfr_options.push(' ');
+        }
+    }
+    let cache_dir = cache_dir.map(|cache| cache.join(&version_prefix).join(cache_target));
 
     let target = target
         .into_string()
@@ -131,12 +167,12 @@ fn main() {
     }
 
     let mut env = Environment {
-        rustc: rustc,
         out_dir: out_dir.clone(),
         lib_dir: out_dir.join("lib"),
         include_dir: out_dir.join("include"),
         build_dir: out_dir.join("build"),
         cache_dir: cache_dir,
+        mpfr_options,
         jobs: cargo_env("NUM_JOBS"),
         target: target,
         make_check: make_check,
@@ -147,7 +183,6 @@ fn main() {
         cflags,
         host,
     };
-    env.check_feature("maybe_uninit", TRY_MAYBE_UNINIT, Some("maybe_uninit"));
 
     // make sure we have target directories
     create_dir_or_panic(&env.lib_dir);
@@ -201,7 +236,16 @@ fn main() {
     if env.newer_cache {
         println!("cargo:rustc-cfg=newer_cache");
     }
+    if mpfr_ah.is_some() {
+        build_mpfr_wrappers(&env);
+    }
     process_gmp_header(&gmp_ah.1, &out_dir.join("gmp_h.rs"));
+    if let Some((_, ref header)) = mpfr_ah {
+        process_version_header(header, "MPFR", &[(4, 1), (4, 2)]);
+    }
+    if let Some((_, ref header)) = mpc_ah {
+        process_version_header(header, "MPC", &[(1, 2), (1, 3)]);
+    }
     write_link_info(&env, workaround_47048, mpfr_ah.is_some(), mpc_ah.is_some());
 }
 
@@ -499,6 +543,7 @@ fn process_gmp_header(header: &Path, out_file: &Path) {
     let mut long_long_limb = None;
     let mut cc = None;
     let mut cflags = None;
+    let mut version = [None; 3];
     let mut reader = open(&header);
     let mut buf = String::new();
     while read_line(&mut reader, &mut buf, &header) > 0 {
@@ -516,6 +561,20 @@ fn process_gmp_header(header: &Path, out_file: &Path) {
         if let Some(start) = buf.find(s) {
             nail_bits = buf[(start + s.len())..].trim().parse::<i32>().ok();
         }
+        // "#define __GNU_MP_VERSION" is a prefix of the other two, so
+        // only accept lines where the rest is a number.
+        let names = [
+            "#define __GNU_MP_VERSION",
+            "#define __GNU_MP_VERSION_MINOR",
+            "#define __GNU_MP_VERSION_PATCHLEVEL",
+        ];
+        for (v, s) in version.iter_mut().zip(names.iter()) {
+            if let Some(start) = buf.find(s) {
+                if let Ok(n) = buf[(start + s.len())..].trim().parse::<i32>() {
+                    *v = Some(n);
+                }
+            }
+        }
         let s = "#define __GMP_CC";
         if let Some(start) = buf.find(s) {
             cc = Some(
@@ -553,6 +612,15 @@ fn process_gmp_header(header: &Path, out_file: &Path) {
     } else {
         "::std::os::raw::c_ulong"
     };
+    let major = version[0].expect("Cannot determine __GNU_MP_VERSION from gmp.h");
+    let minor = version[1].expect("Cannot determine __GNU_MP_VERSION_MINOR from gmp.h");
+    let patchlevel = version[2].expect("Cannot determine __GNU_MP_VERSION_PATCHLEVEL from gmp.h");
+    for &(cfg_major, cfg_minor) in &[(6, 2), (6, 3)] {
+        if (major, minor) >= (cfg_major, cfg_minor) {
+            println!("cargo:rustc-cfg=gmp_{}_{}", cfg_major, cfg_minor);
+        }
+    }
+
     let cc = cc.expect("Cannot determine __GMP_CC from gmp.h");
     let cflags = cflags.expect("Cannot determine __GMP_CFLAGS from gmp.h");
     let content = format!(
@@ -560,12 +628,15 @@ fn process_gmp_header(header: &Path, out_file: &Path) {
             "const GMP_LIMB_BITS: c_int = {};\n",
             "const GMP_NAIL_BITS: c_int = {};\n",
             "type GMP_LIMB_T = {};\n",
+            "const GMP_VERSION: c_int = {};\n",
+            "const GMP_VERSION_MINOR: c_int = {};\n",
+            "const GMP_VERSION_PATCHLEVEL: c_int = {};\n",
             "const GMP_CC: *const c_char =\n",
             "    b\"{}\\0\" as *const _ as _;\n",
             "const GMP_CFLAGS: *const c_char =\n",
             "    b\"{}\\0\" as *const _ as _;\n"
         ),
-        limb_bits, nail_bits, long_long_limb, cc, cflags
+        limb_bits, nail_bits, long_long_limb, major, minor, patchlevel, cc, cflags
     );
 
     let mut rs = create(out_file);
@@ -573,6 +644,36 @@ fn process_gmp_header(header: &Path, out_file: &Path) {
     flush(&mut rs, out_file);
 }
 
+// Emits a cfg for every minor version with new functions, so that
+// for example MPFR 4.2 enables both mpfr_4_1 and mpfr_4_2.
+fn process_version_header(header: &Path, prefix: &str, versions: &[(i32, i32)]) {
+    let mut major = None;
+    let mut minor = None;
+    let major_def = format!("#define {}_VERSION_MAJOR", prefix);
+    let minor_def = format!("#define {}_VERSION_MINOR", prefix);
+    let mut reader = open(&header);
+    let mut buf = String::new();
+    while read_line(&mut reader, &mut buf, &header) > 0 {
+        if let Some(start) = buf.find(&major_def) {
+            major = buf[(start + major_def.len())..].trim().parse::<i32>().ok();
+        }
+        if let Some(start) = buf.find(&minor_def) {
+            minor = buf[(start + minor_def.len())..].trim().parse::<i32>().ok();
+        }
+        buf.clear();
+    }
+    drop(reader);
+
+    let major = major.unwrap_or_else(|| panic!("Cannot determine {}_VERSION_MAJOR", prefix));
+    let minor = minor.unwrap_or_else(|| panic!("Cannot determine {}_VERSION_MINOR", prefix));
+    let lower = prefix.to_lowercase();
+    for &(cfg_major, cfg_minor) in versions {
+        if (major, minor) >= (cfg_major, cfg_minor) {
+            println!("cargo:rustc-cfg={}_{}_{}", lower, cfg_major, cfg_minor);
+        }
+    }
+}
+
 fn build_mpfr(env: &Environment, lib: &Path, header: &Path) {
     let build_dir = env.build_dir.join("mpfr-build");
     create_dir_or_panic(&build_dir);
@@ -590,8 +691,8 @@ fn build_mpfr(env: &Environment, lib: &Path, header: &Path) {
          --disable-shared \
          --with-pic \
          --with-gmp-build=../gmp-build \
-         ",
-        &env.host
+         {}",
+        &env.host, &env.mpfr_options
     );
     let mut configure = Command::new("sh");
     configure
@@ -650,6 +751,53 @@ fn build_mpc(env: &Environment, lib: &Path, header: &Path) {
     copy_file_or_panic(&src_header, &header);
 }
 
+fn has_x87_long_double() -> bool {
+    cargo_env("CARGO_CFG_TARGET_ARCH") == OsString::from("x86_64")
+        && cargo_env("CARGO_CFG_TARGET_OS") == OsString::from("linux")
+}
+
+// The wrappers are small, so they are compiled on every build instead
+// of being cached with the libraries.
+fn build_mpfr_wrappers(env: &Environment) {
+    let ld = has_x87_long_double();
+    let float128 = there_is_env("CARGO_FEATURE_FLOAT128");
+    let decimal = there_is_env("CARGO_FEATURE_DECIMAL_FLOAT");
+    if !ld && !float128 && !decimal {
+        return;
+    }
+    let src = env.out_dir.join("mpfr_wrappers.c");
+    create_file_or_panic(&src, MPFR_WRAPPERS_C);
+    let mut build = cc::Build::new();
+    build.file(&src).include(&env.include_dir);
+    if ld {
+        build.define("WANT_LD", None);
+    }
+    if float128 {
+        build.define("MPFR_WANT_FLOAT128", None);
+    }
+    if decimal {
+        build.define("MPFR_WANT_DECIMAL_FLOATS", None);
+    }
+    build.compile("gmp_mpfr_sys_wrappers");
+    if ld {
+        build_ld_test_helpers(env);
+    }
+}
+
+// The long double test helpers go into a separate library that is
+// only linked by the tests, which name it in a #[link] attribute, so
+// that they are not part of the wrapper library linked into every
+// dependent crate.
+fn build_ld_test_helpers(env: &Environment) {
+    let src = env.out_dir.join("ld_test_helpers.c");
+    create_file_or_panic(&src, LD_TEST_HELPERS_C);
+    cc::Build::new()
+        .file(&src)
+        .cargo_metadata(false)
+        .compile("gmp_mpfr_sys_ld_test");
+    println!("cargo:rustc-link-search=native={}", env.out_dir.display());
+}
+
 fn write_link_info(
     env: &Environment,
     workaround_47048: Workaround47048,
@@ -693,52 +841,6 @@ fn write_link_info(
     }
 }
 
-impl Environment {
-    fn check_feature(&self, name: &str, contents: &str, nightly_features: Option<&str>) {
-        let try_dir = self.out_dir.join(format!("try_{}", name));
-        let filename = format!("try_{}.rs", name);
-        create_dir_or_panic(&try_dir);
-        println!("$ cd {:?}", try_dir);
-
-        enum Iteration {
-            Stable,
-            Unstable,
-        }
-        for i in &[Iteration::Stable, Iteration::Unstable] {
-            let s;
-            let file_contents = match *i {
-                Iteration::Stable => contents,
-                Iteration::Unstable => match nightly_features {
-                    Some(features) => {
-                        s = format!("#![feature({})]\n{}", features, contents);
-                        &s
-                    }
-                    None => continue,
-                },
-            };
-            create_file_or_panic(&try_dir.join(&filename), file_contents);
-            let mut cmd = Command::new(&self.rustc);
-            cmd.current_dir(&try_dir)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .args(&[&*filename, "--emit=dep-info,metadata"]);
-            println!("$ {:?} >& /dev/null", cmd);
-            let status = cmd
-                .status()
-                .unwrap_or_else(|_| panic!("Unable to execute: {:?}", cmd));
-            if status.success() {
-                println!("cargo:rustc-cfg={}", name);
-                if let Iteration::Unstable = *i {
-                    println!("cargo:rustc-cfg=nightly_{}", name);
-                }
-                break;
-            }
-        }
-
-        remove_dir_or_panic(&try_dir);
-    }
-}
-
 fn cargo_env(name: &str) -> OsString {
     env::var_os(name)
         .unwrap_or_else(|| panic!("environment variable not found: {}, please use cargo", name))
@@ -1006,17 +1108,6 @@ fn flush(writer: &mut BufWriter<File>, name: &Path) {
         .unwrap_or_else(|_| panic!("Cannot write to: {:?}", name));
 }
 
-const TRY_MAYBE_UNINIT: &'static str = r#"// try_maybe_uninit.rs
-use std::mem::MaybeUninit;
-fn main() {
-    let mut x = MaybeUninit::<u8>::zeroed();
-    let _ = x.as_ptr();
-    let _ = x.as_mut_ptr();
-    let _ = unsafe { x.assume_init() };
-    let _ = MaybeUninit::<u8>::uninit();
-}
-"#;
-
 const BUG_47048_SAY_HI_C: &'static str = r#"/* say_hi.c */
 #include <stdio.h>
 void say_hi(void) {
@@ -1055,3 +1146,82 @@ FILE *__cdecl __acrt_iob_func(unsigned index)
 typedef FILE *__cdecl (*_f__acrt_iob_func)(unsigned index);
 _f__acrt_iob_func __MINGW_IMP_SYMBOL(__acrt_iob_func) = __acrt_iob_func;
 "#;
+
+const MPFR_WRAPPERS_C: &'static str = r#"/* mpfr_wrappers.c */
+#include <string.h>
+#include <gmp.h>
+#include <mpfr.h>
+
+#ifdef WANT_LD
+
+int gmp_mpfr_sys_mpfr_set_ld(mpfr_ptr rop, const long double *op, mpfr_rnd_t rnd)
+{
+    return mpfr_set_ld(rop, *op, rnd);
+}
+
+void gmp_mpfr_sys_mpfr_get_ld(long double *rop, mpfr_srcptr op, mpfr_rnd_t rnd)
+{
+    *rop = mpfr_get_ld(op, rnd);
+}
+
+void gmp_mpfr_sys_mpfr_get_ld_2exp(long double *rop, long *exp, mpfr_srcptr op,
+                                   mpfr_rnd_t rnd)
+{
+    *rop = mpfr_get_ld_2exp(exp, op, rnd);
+}
+
+int gmp_mpfr_sys_mpfr_cmp_ld(mpfr_srcptr op1, const long double *op2)
+{
+    return mpfr_cmp_ld(op1, *op2);
+}
+#endif
+
+#ifdef MPFR_WANT_FLOAT128
+/* Rust may not align the bit pattern to 16 bytes, so use memcpy. */
+
+int gmp_mpfr_sys_mpfr_set_float128(mpfr_ptr rop, const void *op, mpfr_rnd_t rnd)
+{
+    __float128 f;
+    memcpy(&f, op, sizeof f);
+    return mpfr_set_float128(rop, f, rnd);
+}
+
+void gmp_mpfr_sys_mpfr_get_float128(void *rop, mpfr_srcptr op, mpfr_rnd_t rnd)
+{
+    __float128 f = mpfr_get_float128(op, rnd);
+    memcpy(rop, &f, sizeof f);
+}
+#endif
+
+#ifdef MPFR_WANT_DECIMAL_FLOATS
+int gmp_mpfr_sys_mpfr_set_decimal64(mpfr_ptr rop, const void *op, mpfr_rnd_t rnd)
+{
+    _Decimal64 d;
+    memcpy(&d, op, sizeof d);
+    return mpfr_set_decimal64(rop, d, rnd);
+}
+
+void gmp_mpfr_sys_mpfr_get_decimal64(void *rop, mpfr_srcptr op, mpfr_rnd_t rnd)
+{
+    _Decimal64 d = mpfr_get_decimal64(op, rnd);
+    memcpy(rop, &d, sizeof d);
+}
+#endif
+"#;
+
+const LD_TEST_HELPERS_C: &'static str = r#"/* ld_test_helpers.c */
+#include <stdlib.h>
+
+/* Used by tests to produce and check long double values independently
+   of MPFR. */
+
+void gmp_mpfr_sys_test_strtold(long double *rop, const char *s)
+{
+    *rop = strtold(s, NULL);
+}
+
+int gmp_mpfr_sys_test_ld_eq(const long double *op1, const long double *op2)
+{
+    return *op1 == *op2;
+}
+"#;
@@ -0,0 +1,346 @@
+// Copyright © 2017–2019 University of Malta
+
+// This program is free software: you can redistribute it and/or
+// modify it under the terms of the GNU Lesser General Public License
+// as published by the Free Software Foundation, either version 3 of
+// the License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public
+// License and a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+
+/*!
+Adapters from Rust [`Read`] and [`Write`] to C `FILE` streams.
+
+Many functions in the bindings take a `*mut FILE` parameter, for
+example [`gmp::mpz_out_str`] and [`gmp::mpz_inp_str`]. The functions
+in this module wrap a Rust reader or writer as a temporary `FILE`
+using `fopencookie`, so that these functions can read from or write
+to anything implementing [`Read`] or [`Write`], such as a
+[`Vec<u8>`], a [`File`] or a [`TcpStream`].
+
+This module is only available on GNU/Linux.
+
+# Examples
+
+```rust
+use gmp_mpfr_sys::file;
+use gmp_mpfr_sys::gmp;
+use std::mem;
+unsafe {
+    let mut z = mem::zeroed();
+    gmp::mpz_init_set_ui(&mut z, 255);
+    let mut v = Vec::new();
+    let written = file::with_writer(&mut v, |fp| gmp::mpz_out_str(fp, 16, &z));
+    assert_eq!(written.unwrap(), 2);
+    assert_eq!(v, b"ff");
+    gmp::mpz_clear(&mut z);
+}
+```
+
+[`File`]: https://doc.rust-lang.org/std/fs/struct.File.html
+[`Read`]: https://doc.rust-lang.org/std/io/trait.Read.html
+[`TcpStream`]: https://doc.rust-lang.org/std/net/struct.TcpStream.html
+[`Vec<u8>`]: https://doc.rust-lang.org/std/vec/struct.Vec.html
+[`Write`]: https://doc.rust-lang.org/std/io/trait.Write.html
+[`gmp::mpz_inp_str`]: ../gmp/fn.mpz_inp_str.html
+[`gmp::mpz_out_str`]: ../gmp/fn.mpz_out_str.html
+*/
+
+use libc::{self, off64_t, size_t, ssize_t, FILE};
+use std::any::Any;
+use std::io::{Error, ErrorKind, Read, Result as IoResult, Write};
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+#[repr(C)]
+struct cookie_io_functions_t {
+    read: Option<unsafe extern "C" fn(*mut c_void, *mut c_char, size_t) -> ssize_t>,
+    write: Option<unsafe extern "C" fn(*mut c_void, *const c_char, size_t) -> ssize_t>,
+    seek: Option<unsafe extern "C" fn(*mut c_void, *mut off64_t, c_int) -> c_int>,
+    close: Option<unsafe extern "C" fn(*mut c_void) -> c_int>,
+}
+
+extern "C" {
+    fn fopencookie(
+        cookie: *mut c_void,
+        mode: *const c_char,
+        io_funcs: cookie_io_functions_t,
+    ) -> *mut FILE;
+}
+
+struct Cookie<'a, T: 'a + ?Sized> {
+    inner: &'a mut T,
+    error: Option<Error>,
+    panic: Option<Box<dyn Any + Send + 'static>>,
+}
+
+impl<'a, T: 'a + ?Sized> Cookie<'a, T> {
+    fn new(inner: &'a mut T) -> Cookie<'a, T> {
+        Cookie {
+            inner,
+            error: None,
+            panic: None,
+        }
+    }
+
+    // Runs an I/O operation on the inner reader or writer, storing
+    // any error or panic so that it can be reported once the FILE is
+    // closed, as neither may cross the C stack frames.
+    fn run<F>(&mut self, f: F) -> Option<usize>
+    where
+        F: FnOnce(&mut T) -> IoResult<usize>,
+    {
+        if self.error.is_some() || self.panic.is_some() {
+            return None;
+        }
+        let inner = &mut *self.inner;
+        match panic::catch_unwind(AssertUnwindSafe(|| f(inner))) {
+            Ok(Ok(n)) => Some(n),
+            Ok(Err(e)) => {
+                self.error = Some(e);
+                None
+            }
+            Err(p) => {
+                self.panic = Some(p);
+                None
+            }
+        }
+    }
+
+    fn finish<R>(self, ret: R, stream_ok: bool) -> IoResult<R> {
+        if let Some(p) = self.panic {
+            panic::resume_unwind(p);
+        }
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        if !stream_ok {
+            return Err(Error::new(ErrorKind::Other, "C stream error"));
+        }
+        Ok(ret)
+    }
+}
+
+unsafe extern "C" fn read_fn<R: Read + ?Sized>(
+    cookie: *mut c_void,
+    buf: *mut c_char,
+    size: size_t,
+) -> ssize_t {
+    let cookie = &mut *(cookie as *mut Cookie<R>);
+    let buf = slice::from_raw_parts_mut(buf as *mut u8, size);
+    let read = cookie.run(|r| loop {
+        match r.read(buf) {
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            res => return res,
+        }
+    });
+    match read {
+        Some(n) => n as ssize_t,
+        None => -1,
+    }
+}
+
+unsafe extern "C" fn write_fn<W: Write + ?Sized>(
+    cookie: *mut c_void,
+    buf: *const c_char,
+    size: size_t,
+) -> ssize_t {
+    let cookie = &mut *(cookie as *mut Cookie<W>);
+    let buf = slice::from_raw_parts(buf as *const u8, size);
+    // The C library treats a short count as an error, so it is only
+    // worth returning size or 0.
+    match cookie.run(|w| w.write_all(buf).map(|()| size)) {
+        Some(n) => n as ssize_t,
+        None => 0,
+    }
+}
+
+/// Calls a function with a temporary `FILE` stream that reads from
+/// `reader`.
+///
+/// The stream is unbuffered, so that as little as possible is read
+/// from `reader` beyond what the C function consumes. Note that
+/// functions such as [`gmp::mpz_inp_str`] and [`gmp::fscanf`] may
+/// read one character of look-ahead; that character is consumed from
+/// `reader` and lost when the stream is closed.
+///
+/// If reading from `reader` fails, the C function sees an error on
+/// the stream, and this function returns the error from `reader`
+/// instead of the return value of `f`. If reading from `reader`
+/// panics, the panic is resumed after the stream is closed.
+///
+/// The stream is closed when `f` returns, so `f` must not close it,
+/// and must not use it after returning.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::file;
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut z = mem::zeroed();
+///     gmp::mpz_init(&mut z);
+///     let mut input: &[u8] = b"-1234 rest";
+///     let read = file::with_reader(&mut input, |fp| gmp::mpz_inp_str(&mut z, fp, 10));
+///     assert_eq!(read.unwrap(), 5);
+///     assert_eq!(gmp::mpz_get_si(&z), -1234);
+///     gmp::mpz_clear(&mut z);
+/// }
+/// ```
+///
+/// [`gmp::fscanf`]: ../gmp/fn.fscanf.html
+/// [`gmp::mpz_inp_str`]: ../gmp/fn.mpz_inp_str.html
+pub fn with_reader<R, F, T>(reader: &mut R, f: F) -> IoResult<T>
+where
+    R: Read + ?Sized,
+    F: FnOnce(*mut FILE) -> T,
+{
+    let mut cookie = Cookie::new(reader);
+    let funcs = cookie_io_functions_t {
+        read: Some(read_fn::<R>),
+        write: None,
+        seek: None,
+        close: None,
+    };
+    let fp = unsafe {
+        fopencookie(
+            &mut cookie as *mut Cookie<R> as *mut c_void,
+            b"r\0".as_ptr() as *const c_char,
+            funcs,
+        )
+    };
+    if fp.is_null() {
+        return Err(Error::last_os_error());
+    }
+    unsafe {
+        libc::setvbuf(fp, ptr::null_mut(), libc::_IONBF, 0);
+    }
+    let ret = f(fp);
+    let closed = unsafe { libc::fclose(fp) } == 0;
+    cookie.finish(ret, closed)
+}
+
+/// Calls a function with a temporary `FILE` stream that writes to
+/// `writer`.
+///
+/// The stream is buffered. When `f` returns, the stream is flushed
+/// and closed, and then `writer` itself is flushed.
+///
+/// If writing to or flushing `writer` fails, the C function sees an
+/// error on the stream, and this function returns the error from
+/// `writer` instead of the return value of `f`. If writing to
+/// `writer` panics, the panic is resumed after the stream is closed.
+///
+/// The stream is closed when `f` returns, so `f` must not close it,
+/// and must not use it after returning.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::file;
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut q = mem::zeroed();
+///     gmp::mpq_init(&mut q);
+///     gmp::mpq_set_si(&mut q, -3, 4);
+///     let mut v = Vec::new();
+///     let fmt = b"q = %Qd\n\0".as_ptr() as _;
+///     let written = file::with_writer(&mut v, |fp| gmp::fprintf(fp, fmt, &q as *const gmp::mpq_t));
+///     assert_eq!(written.unwrap(), 9);
+///     assert_eq!(v, b"q = -3/4\n");
+///     gmp::mpq_clear(&mut q);
+/// }
+/// ```
+pub fn with_writer<W, F, T>(writer: &mut W, f: F) -> IoResult<T>
+where
+    W: Write + ?Sized,
+    F: FnOnce(*mut FILE) -> T,
+{
+    let mut cookie = Cookie::new(writer);
+    let funcs = cookie_io_functions_t {
+        read: None,
+        write: Some(write_fn::<W>),
+        seek: None,
+        close: None,
+    };
+    let fp = unsafe {
+        fopencookie(
+            &mut cookie as *mut Cookie<W> as *mut c_void,
+            b"w\0".as_ptr() as *const c_char,
+            funcs,
+        )
+    };
+    if fp.is_null() {
+        return Err(Error::last_os_error());
+    }
+    let ret = f(fp);
+    let closed = unsafe { libc::fclose(fp) } == 0;
+    if closed {
+        cookie.run(|w| w.flush().map(|()| 0));
+    }
+    cookie.finish(ret, closed)
+}
+
+#[cfg(test)]
+mod tests {
+    use file;
+    use gmp;
+    use std::io::{Error, ErrorKind, Result as IoResult, Write};
+    use std::mem;
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> IoResult<usize> {
+            Err(Error::new(ErrorKind::BrokenPipe, "failing writer"))
+        }
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn check_round_trip() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            let digits = "-123456789012345678901234567890";
+            gmp::mpz_set_str(&mut z, format!("{}\0", digits).as_ptr() as _, 10);
+
+            let mut v = Vec::new();
+            let written = file::with_writer(&mut v, |fp| gmp::mpz_out_str(fp, 10, &z)).unwrap();
+            assert_eq!(written, digits.len());
+            assert_eq!(v, digits.as_bytes());
+
+            let mut back = mem::zeroed();
+            gmp::mpz_init(&mut back);
+            let read = file::with_reader(&mut &v[..], |fp| gmp::mpz_inp_str(&mut back, fp, 10));
+            assert_eq!(read.unwrap(), digits.len());
+            assert_eq!(gmp::mpz_cmp(&z, &back), 0);
+
+            gmp::mpz_clear(&mut back);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+
+    #[test]
+    fn check_write_error() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init_set_ui(&mut z, 1);
+            let res = file::with_writer(&mut FailingWriter, |fp| gmp::mpz_out_str(fp, 10, &z));
+            assert_eq!(res.unwrap_err().kind(), ErrorKind::BrokenPipe);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+}
@@ -0,0 +1,493 @@
+// Copyright © 2017–2019 University of Malta
+
+// This program is free software: you can redistribute it and/or
+// modify it under the terms of the GNU Lesser General Public License
+// as published by the Free Software Foundation, either version 3 of
+// the License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public
+// License and a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+
+/*!
+Type-checked formatted output.
+
+The [`gmp_format!`] macro formats GMP and MPFR numbers into a
+[`String`] using the conversion specifications of [`gmp::asprintf`]
+and [`mpfr::asprintf`], but unlike those variadic functions, it checks
+the conversion specifications against the types of the arguments when
+the crate is compiled.
+
+The supported conversion specifications are:
+
+| Specification | Argument                | Conversions               |
+|---------------|-------------------------|---------------------------|
+| `%Z`          | [`gmp::mpz_t`]          | `d i o u x X`             |
+| `%Q`          | [`gmp::mpq_t`]          | `d i o u x X`             |
+| `%F`          | [`gmp::mpf_t`]          | `a A e E f g G`           |
+| `%R`          | [`mpfr::mpfr_t`]        | `a A b e E f F g G`       |
+| `%N`          | slice of [`gmp::limb_t`] | `d i o u x X`            |
+
+Each specification can have the flags `-`, `+`, space, `#`, `0` and
+`'`, a field width and a precision, for example `%-+12Zd` or `%.20Ff`.
+The `%R` specification can also have a rounding mode `U`, `D`, `Y`,
+`Z` or `N` before the conversion, for example `%.10RDf`. The width and
+precision cannot be specified as `*`. A literal `%` is written as
+`%%`.
+
+An argument can be a reference or a raw pointer to the number, so
+`&z`, `&mut z` and `&z as *const gmp::mpz_t` are all accepted for a
+`%Zd` specification.
+
+# Examples
+
+```rust
+use gmp_mpfr_sys::gmp;
+use std::mem;
+unsafe {
+    let mut z = mem::zeroed();
+    gmp::mpz_init_set_si(&mut z, -255);
+    let mut q = mem::zeroed();
+    gmp::mpq_init(&mut q);
+    gmp::mpq_set_si(&mut q, 3, 4);
+    let s = gmp_mpfr_sys::gmp_format!("%Zd = %#Zx, %Qd = 75%%", &z, &z, &q);
+    assert_eq!(s, "-255 = -0xff, 3/4 = 75%");
+    gmp::mpq_clear(&mut q);
+    gmp::mpz_clear(&mut z);
+}
+```
+
+[`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+[`gmp::asprintf`]: ../gmp/fn.asprintf.html
+[`gmp::limb_t`]: ../gmp/type.limb_t.html
+[`gmp::mpf_t`]: ../gmp/struct.mpf_t.html
+[`gmp::mpq_t`]: ../gmp/struct.mpq_t.html
+[`gmp::mpz_t`]: ../gmp/struct.mpz_t.html
+[`gmp_format!`]: ../macro.gmp_format.html
+[`mpfr::asprintf`]: ../mpfr/fn.asprintf.html
+[`mpfr::mpfr_t`]: ../mpfr/struct.mpfr_t.html
+*/
+
+use gmp;
+#[cfg(feature = "mpfr")]
+use mpfr;
+use std::ffi::{CStr, CString};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+/// Formats GMP and MPFR numbers into a [`String`], checking the
+/// conversion specifications against the argument types at compile
+/// time.
+///
+/// The first argument is the format string, which must be a constant
+/// expression of type `&'static str`, such as a string literal. The
+/// supported conversion specifications are listed in the [`format`]
+/// module.
+///
+/// The macro must be used inside an `unsafe` block, as all the
+/// arguments must point to initialized numbers.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     gmp::mpf_init_set_d(&mut f, 1.5);
+///     let limbs: [gmp::limb_t; 1] = [10];
+///     let s = gmp_mpfr_sys::gmp_format!("[%8.3Ff] [%-4Nd]", &f, &limbs[..]);
+///     assert_eq!(s, "[   1.500] [10  ]");
+///     gmp::mpf_clear(&mut f);
+/// }
+/// ```
+///
+/// Mismatched arguments are a compile-time error.
+///
+/// ```rust,compile_fail
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut q: gmp::mpq_t = mem::zeroed();
+///     gmp::mpq_init(&mut q);
+///     // %Zd expects an mpz_t, not an mpq_t
+///     let _ = gmp_mpfr_sys::gmp_format!("%Zd", &q);
+///     gmp::mpq_clear(&mut q);
+/// }
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`format`]: format/index.html
+#[macro_export]
+macro_rules! gmp_format {
+    ($fmt:expr $(, $arg:expr)* $(,)?) => {{
+        struct Template;
+        impl $crate::format::Template for Template {
+            const FMT: &'static str = $fmt;
+        }
+        $crate::format::format::<Template, _>(($(&$arg,)*))
+    }};
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub enum Kind {
+    Z,
+    Q,
+    F,
+    R,
+    N,
+}
+
+/// An argument for a conversion specification of [`gmp_format!`].
+///
+/// This trait is implemented for [`gmp::mpz_t`], [`gmp::mpq_t`],
+/// [`gmp::mpf_t`], [`mpfr::mpfr_t`] and slices of [`gmp::limb_t`], as
+/// well as for references and raw pointers to them.
+///
+/// [`gmp::limb_t`]: ../gmp/type.limb_t.html
+/// [`gmp::mpf_t`]: ../gmp/struct.mpf_t.html
+/// [`gmp::mpq_t`]: ../gmp/struct.mpq_t.html
+/// [`gmp::mpz_t`]: ../gmp/struct.mpz_t.html
+/// [`gmp_format!`]: ../macro.gmp_format.html
+/// [`mpfr::mpfr_t`]: ../mpfr/struct.mpfr_t.html
+pub trait Arg {
+    #[doc(hidden)]
+    const KIND: Kind;
+    #[doc(hidden)]
+    unsafe fn asprintf(&self, spec: &CStr) -> String;
+}
+
+unsafe fn gmp_string(s: *mut c_char, ret: c_int) -> String {
+    assert!(ret >= 0, "formatting failed");
+    let string = CStr::from_ptr(s).to_string_lossy().into_owned();
+    gmp::free_str(s);
+    string
+}
+
+impl Arg for gmp::mpz_t {
+    const KIND: Kind = Kind::Z;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        let mut s = ptr::null_mut();
+        let ret = gmp::asprintf(&mut s, spec.as_ptr(), self as *const gmp::mpz_t);
+        gmp_string(s, ret)
+    }
+}
+
+impl Arg for gmp::mpq_t {
+    const KIND: Kind = Kind::Q;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        let mut s = ptr::null_mut();
+        let ret = gmp::asprintf(&mut s, spec.as_ptr(), self as *const gmp::mpq_t);
+        gmp_string(s, ret)
+    }
+}
+
+impl Arg for gmp::mpf_t {
+    const KIND: Kind = Kind::F;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        let mut s = ptr::null_mut();
+        let ret = gmp::asprintf(&mut s, spec.as_ptr(), self as *const gmp::mpf_t);
+        gmp_string(s, ret)
+    }
+}
+
+impl Arg for [gmp::limb_t] {
+    const KIND: Kind = Kind::N;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        let mut s = ptr::null_mut();
+        let size = self.len() as gmp::size_t;
+        let ret = gmp::asprintf(&mut s, spec.as_ptr(), self.as_ptr(), size);
+        gmp_string(s, ret)
+    }
+}
+
+#[cfg(feature = "mpfr")]
+impl Arg for mpfr::mpfr_t {
+    const KIND: Kind = Kind::R;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        let mut s = ptr::null_mut();
+        let ret = mpfr::asprintf(&mut s, spec.as_ptr(), self as *const mpfr::mpfr_t);
+        assert!(ret >= 0, "formatting failed");
+        let string = CStr::from_ptr(s).to_string_lossy().into_owned();
+        mpfr::free_str(s);
+        string
+    }
+}
+
+impl<T: Arg + ?Sized> Arg for &T {
+    const KIND: Kind = T::KIND;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        (**self).asprintf(spec)
+    }
+}
+
+impl<T: Arg + ?Sized> Arg for &mut T {
+    const KIND: Kind = T::KIND;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        (**self).asprintf(spec)
+    }
+}
+
+impl<T: Arg + ?Sized> Arg for *const T {
+    const KIND: Kind = T::KIND;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        (**self).asprintf(spec)
+    }
+}
+
+impl<T: Arg + ?Sized> Arg for *mut T {
+    const KIND: Kind = T::KIND;
+    unsafe fn asprintf(&self, spec: &CStr) -> String {
+        (**self).asprintf(spec)
+    }
+}
+
+#[doc(hidden)]
+pub trait Template {
+    const FMT: &'static str;
+}
+
+#[doc(hidden)]
+pub trait ArgList {
+    const KINDS: &'static [Kind];
+    unsafe fn write(self, f: &mut Formatter);
+}
+
+macro_rules! arg_list {
+    ($($T:ident $i:tt),*) => {
+        impl<'a, $($T: Arg + ?Sized),*> ArgList for ($(&'a $T,)*) {
+            const KINDS: &'static [Kind] = &[$($T::KIND),*];
+            #[allow(unused_variables)]
+            unsafe fn write(self, f: &mut Formatter) {
+                $(f.arg(self.$i);)*
+            }
+        }
+    };
+}
+
+arg_list! {}
+arg_list! { A 0 }
+arg_list! { A 0, B 1 }
+arg_list! { A 0, B 1, C 2 }
+arg_list! { A 0, B 1, C 2, D 3 }
+arg_list! { A 0, B 1, C 2, D 3, E 4 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10 }
+arg_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11 }
+
+// Finds the next conversion specification in fmt at or after index
+// i, returning its start, its end, and its kind or None for "%%". If
+// there are no more specifications, returns (fmt.len(), fmt.len(),
+// None).
+const fn next_spec(fmt: &[u8], mut i: usize) -> (usize, usize, Option<Kind>) {
+    let len = fmt.len();
+    while i < len && fmt[i] != b'%' {
+        i += 1;
+    }
+    if i == len {
+        return (len, len, None);
+    }
+    let start = i;
+    i += 1;
+    if i < len && fmt[i] == b'%' {
+        return (start, i + 1, None);
+    }
+    while i < len && matches!(fmt[i], b'-' | b'+' | b' ' | b'#' | b'0' | b'\'') {
+        i += 1;
+    }
+    while i < len && fmt[i].is_ascii_digit() {
+        i += 1;
+    }
+    if i < len && fmt[i] == b'.' {
+        i += 1;
+        while i < len && fmt[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    if i == len {
+        panic!("incomplete conversion specification");
+    }
+    let kind = match fmt[i] {
+        b'Z' => Kind::Z,
+        b'Q' => Kind::Q,
+        b'F' => Kind::F,
+        b'R' => Kind::R,
+        b'N' => Kind::N,
+        b'*' => panic!("`*` width or precision is not supported"),
+        _ => panic!("unsupported conversion specification, expected %Z, %Q, %F, %R or %N"),
+    };
+    i += 1;
+    if let Kind::R = kind {
+        if i < len && matches!(fmt[i], b'U' | b'D' | b'Y' | b'Z' | b'N') {
+            i += 1;
+        }
+    }
+    if i == len {
+        panic!("incomplete conversion specification");
+    }
+    let valid = match kind {
+        Kind::Z | Kind::Q | Kind::N => {
+            matches!(fmt[i], b'd' | b'i' | b'o' | b'u' | b'x' | b'X')
+        }
+        Kind::F => matches!(fmt[i], b'a' | b'A' | b'e' | b'E' | b'f' | b'g' | b'G'),
+        Kind::R => matches!(
+            fmt[i],
+            b'a' | b'A' | b'b' | b'e' | b'E' | b'f' | b'F' | b'g' | b'G'
+        ),
+    };
+    if !valid {
+        panic!("invalid conversion for the argument type");
+    }
+    (start, i + 1, Some(kind))
+}
+
+const fn validate(fmt: &str, kinds: &[Kind]) {
+    let fmt = fmt.as_bytes();
+    let mut i = 0;
+    while i < fmt.len() {
+        if fmt[i] == 0 {
+            panic!("format string contains a nul byte");
+        }
+        i += 1;
+    }
+    let mut n = 0;
+    i = 0;
+    loop {
+        let (start, end, kind) = next_spec(fmt, i);
+        if start == fmt.len() {
+            break;
+        }
+        if let Some(kind) = kind {
+            if n == kinds.len() {
+                panic!("too few arguments for format string");
+            }
+            if kinds[n] as u8 != kind as u8 {
+                panic!("argument type does not match conversion specification");
+            }
+            n += 1;
+        }
+        i = end;
+    }
+    if n != kinds.len() {
+        panic!("too many arguments for format string");
+    }
+}
+
+// Evaluating OK is a compile-time error if the arguments do not match
+// the template.
+struct Check<T, A>(PhantomData<(T, A)>);
+
+impl<T: Template, A: ArgList> Check<T, A> {
+    const OK: () = validate(T::FMT, A::KINDS);
+}
+
+#[doc(hidden)]
+pub struct Formatter {
+    fmt: &'static str,
+    pos: usize,
+    out: String,
+}
+
+impl Formatter {
+    // Appends the literal text up to the next conversion
+    // specification and returns the specification.
+    fn advance(&mut self) -> Option<&'static str> {
+        loop {
+            let (start, end, kind) = next_spec(self.fmt.as_bytes(), self.pos);
+            self.out.push_str(&self.fmt[self.pos..start]);
+            self.pos = end;
+            match kind {
+                Some(_) => return Some(&self.fmt[start..end]),
+                None if start == end => return None,
+                None => self.out.push('%'),
+            }
+        }
+    }
+
+    unsafe fn arg<A: Arg + ?Sized>(&mut self, arg: &A) {
+        let spec = self.advance().expect("too many arguments");
+        let spec = CString::new(spec).expect("nul byte in specification");
+        self.out.push_str(&arg.asprintf(&spec));
+    }
+}
+
+#[doc(hidden)]
+pub unsafe fn format<T: Template, A: ArgList>(args: A) -> String {
+    #[allow(clippy::let_unit_value)]
+    let () = Check::<T, A>::OK;
+    let mut f = Formatter {
+        fmt: T::FMT,
+        pos: 0,
+        out: String::new(),
+    };
+    args.write(&mut f);
+    let end = f.advance();
+    debug_assert!(end.is_none());
+    f.out
+}
+
+#[cfg(test)]
+mod tests {
+    use gmp;
+    #[cfg(feature = "mpfr")]
+    use mpfr;
+    use std::mem;
+
+    #[test]
+    fn check_gmp_types() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init_set_si(&mut z, -42);
+            let mut q = mem::zeroed();
+            gmp::mpq_init(&mut q);
+            gmp::mpq_set_si(&mut q, -1, 3);
+            let mut f = mem::zeroed();
+            gmp::mpf_init_set_d(&mut f, 0.25);
+            let limbs: [gmp::limb_t; 2] = [0, 1];
+            let zp = &z as *const gmp::mpz_t;
+
+            assert_eq!(gmp_format!("no args"), "no args");
+            assert_eq!(gmp_format!("%5Zd|%-5Zd", &z, zp), "  -42|-42  ");
+            assert_eq!(gmp_format!("%+Zd", &mut z), "-42");
+            assert_eq!(gmp_format!("%Qd %#Qx", &q, &q), "-1/3 -0x1/0x3");
+            assert_eq!(gmp_format!("%.3Fe 100%%", &f), "2.500e-01 100%");
+            let hex = format!("1{}", "0".repeat(gmp::LIMB_BITS as usize / 4));
+            assert_eq!(gmp_format!("%Nx", &limbs[..]), hex);
+            assert_eq!(gmp_format!("[%Nd]", &[][..] as &[gmp::limb_t]), "[0]");
+
+            gmp::mpf_clear(&mut f);
+            gmp::mpq_clear(&mut q);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+
+    #[cfg(feature = "mpfr")]
+    #[test]
+    fn check_mpfr() {
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 53);
+            mpfr::set_d(&mut f, 2.0 / 3.0, mpfr::rnd_t::RNDN);
+            let mut z = mem::zeroed();
+            gmp::mpz_init_set_ui(&mut z, 7);
+            let s = gmp_format!("%.3Rf %.3RDf %.3RUf %Rb %Zd", &f, &f, &f, &f, &z);
+            assert_eq!(
+                s,
+                "0.667 0.666 0.667 1.0101010101010101010101010101010101010101010101010101p-1 7"
+            );
+            gmp::mpz_clear(&mut z);
+            mpfr::clear(&mut f);
+        }
+    }
+}
@@ -20,19 +20,14 @@ Function and type bindings for the [GMP] library.
 # Examples
 
 ```rust
-# #[cfg(all(maybe_uninit, not(nightly_maybe_uninit)))] {
 use gmp_mpfr_sys::gmp;
-use std::mem::MaybeUninit;
+let mut z = gmp::mpz_new();
 unsafe {
-    let mut z = MaybeUninit::uninit();
-    gmp::mpz_init(z.as_mut_ptr());
-    let mut z = z.assume_init();
     gmp::mpz_set_ui(&mut z, 15);
     let u = gmp::mpz_get_ui(&z);
     assert_eq!(u, 15);
     gmp::mpz_clear(&mut z);
 }
-# }
 ```
 
 [GMP]: https://gmplib.org/
@@ -41,7 +36,14 @@ unsafe {
 
 use libc::FILE;
 use misc;
+use std::cell::{Cell, UnsafeCell};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::mem::MaybeUninit;
 use std::os::raw::{c_char, c_int, c_long, c_uchar, c_uint, c_ulong, c_ushort, c_void};
+use std::ptr;
 
 include!(concat!(env!("OUT_DIR"), "/gmp_h.rs"));
 
@@ -51,11 +53,15 @@ extern "C" {
     pub static bits_per_limb: c_int;
 }
 /// See: [`__GNU_MP_VERSION`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/GMP-Basics.html#index-_005f_005fGNU_005fMP_005fVERSION)
-pub const VERSION: c_int = 6;
+///
+/// This is the version of the GMP library that is built, which is
+/// taken from its header file. Bindings for functions that were added
+/// after GMP 6.1 are only available with newer versions.
+pub const VERSION: c_int = GMP_VERSION;
 /// See: [`__GNU_MP_VERSION_MINOR`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/GMP-Basics.html#index-_005f_005fGNU_005fMP_005fVERSION_005fMINOR)
-pub const VERSION_MINOR: c_int = 1;
+pub const VERSION_MINOR: c_int = GMP_VERSION_MINOR;
 /// See: [`__GNU_MP_VERSION_PATCHLEVEL`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/GMP-Basics.html#index-_005f_005fGNU_005fMP_005fVERSION_005fPATCHLEVEL)
-pub const VERSION_PATCHLEVEL: c_int = 2;
+pub const VERSION_PATCHLEVEL: c_int = GMP_VERSION_PATCHLEVEL;
 extern "C" {
     /// See: [`gmp_version`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/GMP-Basics.html#index-gmp_005fversion)
     #[link_name = "__gmp_version"]
@@ -99,6 +105,10 @@ pub type bitcnt_t = c_ulong;
 /// makes use of these internals should list the dependency as
 /// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
 ///
+/// # Thread safety
+///
+/// See the [crate documentation](../index.html#thread-safety).
+///
 /// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -111,6 +121,13 @@ pub struct mpz_t {
     pub d: *mut limb_t,
 }
 
+// The limbs that `d` points to are allocated for this integer alone
+// and are only reallocated or freed through `mpz_ptr`, while the mpz
+// functions only read their `mpz_srcptr` operands, so concurrent
+// reads are fine.
+unsafe impl Send for mpz_t {}
+unsafe impl Sync for mpz_t {}
+
 /// See: [`mpq_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/GMP-Basics.html#index-mpq_005ft)
 /// and [Rational Internals](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Internals.html#Rational-Internals)
 ///
@@ -122,6 +139,10 @@ pub struct mpz_t {
 /// makes use of these internals should list the dependency as
 /// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
 ///
+/// # Thread safety
+///
+/// See the [crate documentation](../index.html#thread-safety).
+///
 /// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
 ///
 #[repr(C)]
@@ -144,6 +165,10 @@ pub struct mpq_t {
 /// makes use of these internals should list the dependency as
 /// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
 ///
+/// # Thread safety
+///
+/// See the [crate documentation](../index.html#thread-safety).
+///
 /// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -158,6 +183,13 @@ pub struct mpf_t {
     pub d: *mut limb_t,
 }
 
+// `d` points to `prec + 1` limbs allocated for this float alone,
+// which are only reallocated by mpf_set_prec through `mpf_ptr`, and
+// the mpf functions only read their `mpf_srcptr` operands, so
+// concurrent reads are fine.
+unsafe impl Send for mpf_t {}
+unsafe impl Sync for mpf_t {}
+
 /// See: [`gmp_randstate_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/GMP-Basics.html#index-gmp_005frandstate_005ft)
 ///
 /// # Future compatibility
@@ -168,7 +200,16 @@ pub struct mpf_t {
 /// makes use of these internals should list the dependency as
 /// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
 ///
+/// # Thread safety
+///
+/// This type is [`Send`] but not [`Sync`], as generating random
+/// numbers modifies the state. See the [crate
+/// documentation](../index.html#thread-safety) for the global state
+/// shared between threads.
+///
 /// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
+/// [`Send`]: https://doc.rust-lang.org/std/marker/trait.Send.html
+/// [`Sync`]: https://doc.rust-lang.org/std/marker/trait.Sync.html
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
 pub struct randstate_t {
@@ -180,6 +221,11 @@ pub struct randstate_t {
     pub algdata: *mut c_void,
 }
 
+// The seed and the algorithm data are allocated for this state alone
+// and are not tied to the thread that initialized it. It is not Sync,
+// as every generating function modifies the state.
+unsafe impl Send for randstate_t {}
+
 // Types for function declarations in this file.
 
 type mpz_srcptr = *const mpz_t;
@@ -216,6 +262,34 @@ extern "C" {
     #[link_name = "__gmpz_realloc2"]
     /// See: [`mpz_realloc2`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005frealloc2)
     pub fn mpz_realloc2(x: mpz_ptr, n: bitcnt_t);
+}
+/// Initializes an [`mpz_t`] using [`mpz_init`] and returns it.
+///
+/// The returned value has to be freed using [`mpz_clear`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// let mut z = gmp::mpz_new();
+/// unsafe {
+///     assert_eq!(gmp::mpz_sgn(&z), 0);
+///     gmp::mpz_clear(&mut z);
+/// }
+/// ```
+///
+/// [`mpz_clear`]: fn.mpz_clear.html
+/// [`mpz_init`]: fn.mpz_init.html
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub fn mpz_new() -> mpz_t {
+    let mut z = MaybeUninit::uninit();
+    unsafe {
+        mpz_init(z.as_mut_ptr());
+        z.assume_init()
+    }
+}
+extern "C" {
 
     // Assignment Functions
 
@@ -307,6 +381,106 @@ extern "C" {
     /// See: [`mpz_get_str`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005fget_005fstr)
     #[link_name = "__gmpz_get_str"]
     pub fn mpz_get_str(str: *mut c_char, base: c_int, op: mpz_srcptr) -> *mut c_char;
+}
+/// Converts `op` to a [`String`] of digits in base `base`.
+///
+/// The buffer passed to [`mpz_get_str`] is allocated by Rust and
+/// sized using [`mpz_sizeinbase`], so there is no memory allocated by
+/// GMP to free.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// # Panics
+///
+/// Panics if `base` is not in the range 2 to 62 or −36 to −2.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut z = mem::zeroed();
+///     gmp::mpz_init_set_si(&mut z, -255);
+///     assert_eq!(gmp::mpz_get_string(16, &z), "-ff");
+///     assert_eq!(gmp::mpz_get_string(-16, &z), "-FF");
+///     gmp::mpz_clear(&mut z);
+/// }
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`mpz_get_str`]: fn.mpz_get_str.html
+/// [`mpz_sizeinbase`]: fn.mpz_sizeinbase.html
+/// [`mpz_t`]: struct.mpz_t.html
+pub unsafe fn mpz_get_string(base: c_int, op: mpz_srcptr) -> String {
+    misc::check_base(base);
+    // one extra byte for the sign and one for the nul terminator
+    let cap = mpz_sizeinbase(op, base.abs()) + 2;
+    let mut buf = Vec::<u8>::with_capacity(cap);
+    mpz_get_str(buf.as_mut_ptr() as *mut c_char, base, op);
+    misc::string_from_buf(buf)
+}
+
+/// Formats an [`mpz_t`] in decimal using [`mpz_get_str`].
+///
+/// The width, fill, alignment, sign and zero-padding options are
+/// supported. [`Debug`] formats the same as [`Display`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp::{self, DisplayMpz};
+/// let mut z = gmp::mpz_new();
+/// unsafe {
+///     gmp::mpz_set_si(&mut z, -255);
+///     let d = DisplayMpz::new(&z);
+///     assert_eq!(format!("{}", d), "-255");
+///     assert_eq!(format!("{:>6}", d), "  -255");
+///     assert_eq!(format!("{:06}", d), "-00255");
+///     gmp::mpz_clear(&mut z);
+/// }
+/// ```
+///
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`mpz_get_str`]: fn.mpz_get_str.html
+/// [`mpz_t`]: struct.mpz_t.html
+#[derive(Clone, Copy)]
+pub struct DisplayMpz<'a> {
+    z: &'a mpz_t,
+}
+
+impl<'a> DisplayMpz<'a> {
+    /// Creates an adapter that formats `z`.
+    ///
+    /// # Safety
+    ///
+    /// `z` must be initialized.
+    #[inline]
+    pub unsafe fn new(z: &'a mpz_t) -> DisplayMpz<'a> {
+        DisplayMpz { z }
+    }
+}
+
+impl<'a> Display for DisplayMpz<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let s = unsafe { mpz_get_string(10, self.z) };
+        match s.strip_prefix('-') {
+            Some(abs) => f.pad_integral(false, "", abs),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+impl<'a> Debug for DisplayMpz<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+extern "C" {
 
     // Arithmetic Functions
 
@@ -544,6 +718,12 @@ extern "C" {
     /// See: [`mpz_nextprime`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005fnextprime)
     #[link_name = "__gmpz_nextprime"]
     pub fn mpz_nextprime(rop: mpz_ptr, op: mpz_srcptr);
+    /// See: [`mpz_prevprime`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005fprevprime)
+    ///
+    /// This function requires GMP 6.3 or later.
+    #[cfg(gmp_6_3)]
+    #[link_name = "__gmpz_prevprime"]
+    pub fn mpz_prevprime(rop: mpz_ptr, op: mpz_srcptr) -> c_int;
     /// See: [`mpz_gcd`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005fgcd)
     #[link_name = "__gmpz_gcd"]
     pub fn mpz_gcd(rop: mpz_ptr, op1: mpz_srcptr, op2: mpz_srcptr);
@@ -763,6 +943,279 @@ extern "C" {
     ) -> *mut c_void;
 }
 
+mod word_sealed {
+    pub trait Sealed {}
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// An unsigned integer type that can be used as a word in
+/// [`mpz_set_words_le`] and related functions.
+///
+/// This trait is implemented for `u8`, `u16`, `u32` and `u64`, and
+/// cannot be implemented outside this crate.
+///
+/// [`mpz_set_words_le`]: fn.mpz_set_words_le.html
+pub trait Word: Copy + word_sealed::Sealed {
+    #[doc(hidden)]
+    const BITS: u32;
+    #[doc(hidden)]
+    fn to_u64(self) -> u64;
+    #[doc(hidden)]
+    fn from_u64(val: u64) -> Self;
+}
+
+macro_rules! word {
+    ($($T:ty)*) => { $(
+        impl Word for $T {
+            const BITS: u32 = <$T>::BITS;
+            #[inline]
+            fn to_u64(self) -> u64 {
+                u64::from(self)
+            }
+            #[inline]
+            fn from_u64(val: u64) -> Self {
+                val as $T
+            }
+        }
+    )* };
+}
+word! { u8 u16 u32 u64 }
+
+// Sets rop to the value with the absolute value given by count
+// little-endian words of word_bits bits each, independently of the
+// limb and nail sizes.
+unsafe fn set_words<I>(rop: mpz_ptr, word_bits: u32, count: usize, words: I, neg: bool)
+where
+    I: Iterator<Item = u64>,
+{
+    let numb_bits = NUMB_BITS as u32;
+    let max_limbs = (count * word_bits as usize).div_ceil(numb_bits as usize);
+    let p = mpz_limbs_write(rop, max_limbs.max(1) as size_t);
+    let mut acc = 0u128;
+    let mut acc_bits = 0;
+    let mut n = 0;
+    for word in words {
+        acc |= u128::from(word) << acc_bits;
+        acc_bits += word_bits;
+        while acc_bits >= numb_bits {
+            *p.add(n) = acc as limb_t & NUMB_MASK;
+            n += 1;
+            acc >>= numb_bits;
+            acc_bits -= numb_bits;
+        }
+    }
+    if acc_bits > 0 {
+        *p.add(n) = acc as limb_t;
+        n += 1;
+    }
+    // mpz_limbs_finish normalizes away high zero limbs
+    let size = n as size_t;
+    mpz_limbs_finish(rop, if neg { -size } else { size });
+}
+
+// Calls f with the count least significant little-endian words of
+// word_bits bits each of the absolute value of op, independently of
+// the limb and nail sizes.
+unsafe fn get_words<F>(op: mpz_srcptr, word_bits: u32, count: usize, mut f: F)
+where
+    F: FnMut(u64),
+{
+    let numb_bits = NUMB_BITS as u32;
+    let mask = !0u64 >> (64 - word_bits);
+    let nlimbs = mpz_size(op);
+    let p = mpz_limbs_read(op);
+    let mut acc = 0u128;
+    let mut acc_bits = 0;
+    let mut limb = 0;
+    for _ in 0..count {
+        while acc_bits < word_bits && limb < nlimbs {
+            acc |= u128::from(*p.add(limb)) << acc_bits;
+            acc_bits += numb_bits;
+            limb += 1;
+        }
+        f(acc as u64 & mask);
+        acc >>= word_bits;
+        acc_bits = acc_bits.saturating_sub(word_bits);
+    }
+}
+
+/// Sets the value of `rop` from a `u128`.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_set_u128(rop: mpz_ptr, op: u128) {
+    let words = [op as u64, (op >> 64) as u64];
+    set_words(rop, 64, 2, words.iter().cloned(), false);
+}
+/// Sets the value of `rop` from an `i128`.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_set_i128(rop: mpz_ptr, op: i128) {
+    let abs = op.unsigned_abs();
+    let words = [abs as u64, (abs >> 64) as u64];
+    set_words(rop, 64, 2, words.iter().cloned(), op < 0);
+}
+/// Returns the 128 least significant bits of the absolute value of
+/// `op`.
+///
+/// Like [`mpz_get_ui`], the sign of `op` is ignored. To find out if
+/// the value will fit, use [`mpz_fits_u128_p`].
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_fits_u128_p`]: fn.mpz_fits_u128_p.html
+/// [`mpz_get_ui`]: fn.mpz_get_ui.html
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_get_u128(op: mpz_srcptr) -> u128 {
+    let mut ret = 0;
+    let mut shift = 0;
+    get_words(op, 64, 2, |word| {
+        ret |= u128::from(word) << shift;
+        shift += 64;
+    });
+    ret
+}
+/// Returns `op` as an `i128`, wrapping around modulo 2<sup>128</sup>
+/// if it does not fit.
+///
+/// To find out if the value will fit, use [`mpz_fits_i128_p`].
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_fits_i128_p`]: fn.mpz_fits_i128_p.html
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_get_i128(op: mpz_srcptr) -> i128 {
+    let abs = mpz_get_u128(op) as i128;
+    if (*op).size < 0 {
+        abs.wrapping_neg()
+    } else {
+        abs
+    }
+}
+/// Sets the value of `rop` from a slice of words, least significant
+/// word first.
+///
+/// The words are treated as the digits of a non-negative number in
+/// base 2<sup>`W::BITS`</sup>, independently of the limb size.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpz_t`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut z = mem::zeroed();
+///     gmp::mpz_init(&mut z);
+///     gmp::mpz_set_words_le(&mut z, &[0x5678u16, 0x1234]);
+///     assert_eq!(gmp::mpz_get_ui(&z), 0x1234_5678);
+///     let mut words = [0u8; 4];
+///     assert_eq!(gmp::mpz_get_words_be(&mut words, &z), 4);
+///     assert_eq!(words, [0x12, 0x34, 0x56, 0x78]);
+///     gmp::mpz_clear(&mut z);
+/// }
+/// ```
+///
+/// [`mpz_t`]: struct.mpz_t.html
+pub unsafe fn mpz_set_words_le<W: Word>(rop: mpz_ptr, words: &[W]) {
+    let iter = words.iter().map(|&w| w.to_u64());
+    set_words(rop, W::BITS, words.len(), iter, false);
+}
+/// Sets the value of `rop` from a slice of words, most significant
+/// word first.
+///
+/// The words are treated as the digits of a non-negative number in
+/// base 2<sup>`W::BITS`</sup>, independently of the limb size.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_t`]: struct.mpz_t.html
+pub unsafe fn mpz_set_words_be<W: Word>(rop: mpz_ptr, words: &[W]) {
+    let iter = words.iter().rev().map(|&w| w.to_u64());
+    set_words(rop, W::BITS, words.len(), iter, false);
+}
+/// Returns the number of words of type `W` required to store the
+/// absolute value of `op`, which is zero if `op` is zero.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_words_len<W: Word>(op: mpz_srcptr) -> usize {
+    if (*op).size == 0 {
+        0
+    } else {
+        mpz_sizeinbase(op, 2).div_ceil(W::BITS as usize)
+    }
+}
+/// Stores the absolute value of `op` into a slice of words, least
+/// significant word first, and returns [`mpz_words_len`].
+///
+/// If the slice is longer than required, the remaining words are set
+/// to zero. If it is shorter, only the least significant words are
+/// stored, like [`mpz_get_ui`]. The sign of `op` is ignored.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_get_ui`]: fn.mpz_get_ui.html
+/// [`mpz_t`]: struct.mpz_t.html
+/// [`mpz_words_len`]: fn.mpz_words_len.html
+pub unsafe fn mpz_get_words_le<W: Word>(words: &mut [W], op: mpz_srcptr) -> usize {
+    let mut iter = words.iter_mut();
+    get_words(op, W::BITS, iter.len(), |w| {
+        *iter.next().unwrap() = W::from_u64(w);
+    });
+    mpz_words_len::<W>(op)
+}
+/// Stores the absolute value of `op` into a slice of words, most
+/// significant word first, and returns [`mpz_words_len`].
+///
+/// If the slice is longer than required, the leading words are set to
+/// zero. If it is shorter, only the least significant words are
+/// stored, like [`mpz_get_ui`]. The sign of `op` is ignored.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_get_ui`]: fn.mpz_get_ui.html
+/// [`mpz_t`]: struct.mpz_t.html
+/// [`mpz_words_len`]: fn.mpz_words_len.html
+pub unsafe fn mpz_get_words_be<W: Word>(words: &mut [W], op: mpz_srcptr) -> usize {
+    let mut iter = words.iter_mut().rev();
+    get_words(op, W::BITS, iter.len(), |w| {
+        *iter.next().unwrap() = W::from_u64(w);
+    });
+    mpz_words_len::<W>(op)
+}
+
 // Miscellaneous Functions
 
 macro_rules! mpz_fits {
@@ -824,6 +1277,166 @@ extern "C" {
     #[link_name = "__gmpz_fits_sshort_p"]
     pub fn mpz_fits_sshort_p(op: mpz_srcptr) -> c_int;
 }
+/// Returns non-zero if `op` fits in a `u128`, zero otherwise.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_fits_u128_p(op: mpz_srcptr) -> c_int {
+    let fits = (*op).size == 0 || ((*op).size > 0 && mpz_sizeinbase(op, 2) <= 128);
+    if fits {
+        1
+    } else {
+        0
+    }
+}
+/// Returns non-zero if `op` fits in an `i128`, zero otherwise.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpz_t`].
+///
+/// [`mpz_t`]: struct.mpz_t.html
+#[inline]
+pub unsafe fn mpz_fits_i128_p(op: mpz_srcptr) -> c_int {
+    let fits = (*op).size == 0 || {
+        let bits = mpz_sizeinbase(op, 2);
+        // −2^127 is the only 128-bit value that fits
+        bits <= 127 || ((*op).size < 0 && bits == 128 && mpz_scan1(op, 0) == 127)
+    };
+    if fits {
+        1
+    } else {
+        0
+    }
+}
+/// An error returned by the `try_get` functions, such as
+/// [`mpz_try_get_i32`] and [`mpfr::try_get_i32`], when a number
+/// cannot be converted to a primitive integer exactly.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp::{self, ConversionError};
+/// let mut q = gmp::mpq_new();
+/// unsafe {
+///     gmp::mpq_set_si(&mut q, 300, 1);
+///     assert_eq!(gmp::mpq_try_get_u16(&q), Ok(300));
+///     assert_eq!(gmp::mpq_try_get_u8(&q), Err(ConversionError::Overflow));
+///     gmp::mpq_set_si(&mut q, 1, 3);
+///     assert_eq!(gmp::mpq_try_get_u8(&q), Err(ConversionError::NotInteger));
+///     gmp::mpq_clear(&mut q);
+/// }
+/// ```
+///
+/// The enum is non-exhaustive, so that more variants can be added in
+/// the future.
+///
+/// [`mpfr::try_get_i32`]: ../mpfr/fn.try_get_i32.html
+/// [`mpz_try_get_i32`]: fn.mpz_try_get_i32.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ConversionError {
+    /// The value is an integer out of the range of the target type,
+    /// or it is infinite.
+    Overflow,
+    /// The value is NaN.
+    Nan,
+    /// The value is not an integer, and the function converts only
+    /// exact values.
+    NotInteger,
+    /// The value is not an integer, but when rounded in the
+    /// requested direction it fits; this is returned by the rounding
+    /// functions such as [`mpfr::try_get_i32_round`].
+    ///
+    /// [`mpfr::try_get_i32_round`]: ../mpfr/fn.try_get_i32_round.html
+    Inexact,
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            ConversionError::Overflow => f.write_str("value out of range"),
+            ConversionError::Nan => f.write_str("value is NaN"),
+            ConversionError::NotInteger => f.write_str("value is not an integer"),
+            ConversionError::Inexact => f.write_str("value fits only after rounding"),
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+// Converts op using mpz_get_ui if it fits in a c_ulong, and using
+// mpz_get_u128 otherwise.
+unsafe fn mpz_try_get_unsigned<T>(op: mpz_srcptr) -> Result<T, ConversionError>
+where
+    T: TryFrom<c_ulong> + TryFrom<u128>,
+{
+    if mpz_fits_ulong_p(op) != 0 {
+        T::try_from(mpz_get_ui(op)).map_err(|_| ConversionError::Overflow)
+    } else if mpz_fits_u128_p(op) != 0 {
+        T::try_from(mpz_get_u128(op)).map_err(|_| ConversionError::Overflow)
+    } else {
+        Err(ConversionError::Overflow)
+    }
+}
+
+// Converts op using mpz_get_si if it fits in a c_long, and using
+// mpz_get_i128 otherwise.
+unsafe fn mpz_try_get_signed<T>(op: mpz_srcptr) -> Result<T, ConversionError>
+where
+    T: TryFrom<c_long> + TryFrom<i128>,
+{
+    if mpz_fits_slong_p(op) != 0 {
+        T::try_from(mpz_get_si(op)).map_err(|_| ConversionError::Overflow)
+    } else if mpz_fits_i128_p(op) != 0 {
+        T::try_from(mpz_get_i128(op)).map_err(|_| ConversionError::Overflow)
+    } else {
+        Err(ConversionError::Overflow)
+    }
+}
+
+macro_rules! mpz_try_get {
+    ($($name:ident($T:ident, $helper:ident);)*) => { $(
+        #[doc = concat!("Converts `op` to [`", stringify!($T), "`].")]
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConversionError::Overflow`] if `op` does not fit.
+        ///
+        /// # Safety
+        ///
+        /// `op` must point to an initialized [`mpz_t`].
+        ///
+        /// [`ConversionError::Overflow`]: enum.ConversionError.html#variant.Overflow
+        #[doc = concat!(
+            "[`", stringify!($T), "`]: https://doc.rust-lang.org/std/primitive.",
+            stringify!($T), ".html"
+        )]
+        /// [`mpz_t`]: struct.mpz_t.html
+        #[inline]
+        pub unsafe fn $name(op: mpz_srcptr) -> Result<$T, ConversionError> {
+            $helper(op)
+        }
+    )* };
+}
+mpz_try_get! {
+    mpz_try_get_i8(i8, mpz_try_get_signed);
+    mpz_try_get_u8(u8, mpz_try_get_unsigned);
+    mpz_try_get_i16(i16, mpz_try_get_signed);
+    mpz_try_get_u16(u16, mpz_try_get_unsigned);
+    mpz_try_get_i32(i32, mpz_try_get_signed);
+    mpz_try_get_u32(u32, mpz_try_get_unsigned);
+    mpz_try_get_i64(i64, mpz_try_get_signed);
+    mpz_try_get_u64(u64, mpz_try_get_unsigned);
+    mpz_try_get_i128(i128, mpz_try_get_signed);
+    mpz_try_get_u128(u128, mpz_try_get_unsigned);
+    mpz_try_get_isize(isize, mpz_try_get_signed);
+    mpz_try_get_usize(usize, mpz_try_get_unsigned);
+}
 /// See: [`mpz_odd_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005fodd_005fp)
 #[inline]
 pub unsafe extern "C" fn mpz_odd_p(op: mpz_srcptr) -> c_int {
@@ -884,6 +1497,359 @@ extern "C" {
     #[link_name = "__gmpz_roinit_n"]
     pub fn mpz_roinit_n(x: mpz_ptr, xp: mp_srcptr, xs: size_t) -> mpz_srcptr;
 }
+/// See: [`MPZ_ROINIT_N`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-MPZ_005fROINIT_005fN)
+#[allow(non_snake_case)]
+#[inline]
+pub const fn MPZ_ROINIT_N(xp: mp_ptr, xs: size_t) -> mpz_t {
+    mpz_t {
+        alloc: 0,
+        size: xs as c_int,
+        d: xp,
+    }
+}
+
+/// Creates a read-only [`mpz_t`] from an integer literal at compile
+/// time.
+///
+/// The argument is a string constant with an optional `-` sign
+/// followed by digits, with the base determined by the prefix as for
+/// [`mpz_set_str`] with base 0: `0x` or `0X` for hexadecimal, `0b` or
+/// `0B` for binary, `0` for octal, and decimal otherwise. Underscores
+/// between digits are ignored. The digits are converted into a
+/// constant limb array when the crate is compiled, and the macro
+/// yields an [`mpz_t`] built using [`MPZ_ROINIT_N`], which can be used
+/// in a `const` item.
+///
+/// As with [`MPZ_ROINIT_N`], the resulting [`mpz_t`] is read-only: it
+/// can be passed wherever an `mpz_srcptr` is expected, but it must
+/// never be passed as an output parameter or cleared.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp::{self, mpz_t};
+/// use std::mem;
+/// const P: mpz_t = gmp_mpfr_sys::mpz_const!("0xffff_ffff_ffff_ffff_ffff_ffff_ffff_ff61");
+/// const M: mpz_t = gmp_mpfr_sys::mpz_const!("-1000");
+/// unsafe {
+///     let mut z = mem::zeroed();
+///     gmp::mpz_init(&mut z);
+///     gmp::mpz_mul(&mut z, &P, &M);
+///     assert_eq!(gmp::mpz_sizeinbase(&P, 2), 128);
+///     assert_eq!(gmp::mpz_sgn(&M), -1);
+///     gmp::mpz_clear(&mut z);
+/// }
+/// ```
+///
+/// An invalid literal is a compile-time error.
+///
+/// ```rust,compile_fail
+/// let _ = gmp_mpfr_sys::mpz_const!("0x12g4");
+/// ```
+///
+/// [`MPZ_ROINIT_N`]: fn.MPZ_ROINIT_N.html
+/// [`mpz_set_str`]: fn.mpz_set_str.html
+/// [`mpz_t`]: struct.mpz_t.html
+#[macro_export]
+macro_rules! mpz_const {
+    ($s:expr) => {{
+        const MAX: usize = $crate::gmp::const_mpz::max_len($s);
+        const RAW: [$crate::gmp::limb_t; MAX] = $crate::gmp::const_mpz::parse::<MAX>($s);
+        const LEN: usize = $crate::gmp::const_mpz::len(&RAW);
+        const LIMBS: &[$crate::gmp::limb_t; LEN] =
+            &$crate::gmp::const_mpz::truncate::<MAX, LEN>(&RAW);
+        $crate::gmp::MPZ_ROINIT_N(
+            LIMBS as *const [$crate::gmp::limb_t; LEN] as *mut $crate::gmp::limb_t,
+            $crate::gmp::const_mpz::size($s, LEN),
+        )
+    }};
+}
+
+#[doc(hidden)]
+pub mod const_mpz {
+    use gmp::{limb_t, size_t, NUMB_BITS, NUMB_MASK};
+
+    // Returns the index of the first digit, whether the literal is
+    // negative, and the base.
+    const fn prefix(s: &[u8]) -> (usize, bool, u32) {
+        let mut i = 0;
+        let neg = !s.is_empty() && s[0] == b'-';
+        if neg {
+            i += 1;
+        }
+        if i + 1 < s.len() && s[i] == b'0' {
+            match s[i + 1] {
+                b'x' | b'X' => return (i + 2, neg, 16),
+                b'b' | b'B' => return (i + 2, neg, 2),
+                _ => return (i + 1, neg, 8),
+            }
+        }
+        (i, neg, 10)
+    }
+
+    const fn digit(c: u8, base: u32) -> u32 {
+        let d = match c {
+            b'0'..=b'9' => (c - b'0') as u32,
+            b'a'..=b'f' => (c - b'a') as u32 + 10,
+            b'A'..=b'F' => (c - b'A') as u32 + 10,
+            _ => panic!("invalid digit in integer literal"),
+        };
+        if d >= base {
+            panic!("invalid digit in integer literal");
+        }
+        d
+    }
+
+    // An upper bound on the number of limbs required.
+    pub const fn max_len(s: &str) -> usize {
+        let s = s.as_bytes();
+        let (mut i, _, base) = prefix(s);
+        let bits_per_digit: usize = match base {
+            2 => 1,
+            8 => 3,
+            _ => 4,
+        };
+        let mut digits = 0;
+        while i < s.len() {
+            if s[i] != b'_' {
+                digits += 1;
+            }
+            i += 1;
+        }
+        if digits == 0 {
+            panic!("integer literal has no digits");
+        }
+        (digits * bits_per_digit).div_ceil(NUMB_BITS as usize)
+    }
+
+    pub const fn parse<const MAX: usize>(s: &str) -> [limb_t; MAX] {
+        let s = s.as_bytes();
+        let (mut i, _, base) = prefix(s);
+        let mut limbs = [0; MAX];
+        while i < s.len() {
+            if s[i] == b'_' {
+                i += 1;
+                continue;
+            }
+            let mut carry = digit(s[i], base) as u128;
+            let mut j = 0;
+            while j < MAX {
+                let t = limbs[j] as u128 * base as u128 + carry;
+                limbs[j] = (t & NUMB_MASK as u128) as limb_t;
+                carry = t >> NUMB_BITS;
+                j += 1;
+            }
+            i += 1;
+        }
+        limbs
+    }
+
+    pub const fn len<const MAX: usize>(limbs: &[limb_t; MAX]) -> usize {
+        let mut len = MAX;
+        while len > 0 && limbs[len - 1] == 0 {
+            len -= 1;
+        }
+        len
+    }
+
+    pub const fn truncate<const MAX: usize, const LEN: usize>(
+        limbs: &[limb_t; MAX],
+    ) -> [limb_t; LEN] {
+        let mut ret = [0; LEN];
+        let mut i = 0;
+        while i < LEN {
+            ret[i] = limbs[i];
+            i += 1;
+        }
+        ret
+    }
+
+    pub const fn size(s: &str, len: usize) -> size_t {
+        let (_, neg, _) = prefix(s.as_bytes());
+        if neg {
+            -(len as size_t)
+        } else {
+            len as size_t
+        }
+    }
+}
+
+/// An integer that stores up to `LIMBS` limbs inline, so that small
+/// values do not need any heap allocation.
+///
+/// For reading, [`as_ptr`] presents the inline limbs to GMP as a
+/// read-only [`mpz_t`] set up using [`mpz_roinit_n`]. For writing,
+/// [`with_mut`] passes a GMP-allocated [`mpz_t`] to a closure and
+/// copies the result back into the inline limbs when it fits. The
+/// GMP-allocated [`mpz_t`] is a per-thread scratch value that is
+/// reused, so that writing a small value does not allocate either.
+/// When the result does not fit, the value spills to the heap and
+/// the scratch value becomes the storage of the integer.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp::{self, InlineMpz};
+/// let mut a = InlineMpz::<1>::new();
+/// let mut b = InlineMpz::<1>::new();
+/// a.with_mut(|a| unsafe { gmp::mpz_set_si(a, -3) });
+/// b.with_mut(|b| unsafe { gmp::mpz_mul_si(b, a.as_ptr(), 7) });
+/// assert!(b.is_inline());
+/// assert_eq!(unsafe { gmp::mpz_get_si(b.as_ptr()) }, -21);
+/// // too large for one limb, so b spills to the heap
+/// b.with_mut(|b| unsafe { gmp::mpz_mul_2exp(b, b, 100) });
+/// assert!(!b.is_inline());
+/// assert_eq!(unsafe { gmp::mpz_sizeinbase(b.as_ptr(), 2) }, 105);
+/// ```
+///
+/// [`as_ptr`]: #method.as_ptr
+/// [`mpz_roinit_n`]: fn.mpz_roinit_n.html
+/// [`mpz_t`]: struct.mpz_t.html
+/// [`with_mut`]: #method.with_mut
+#[derive(Debug)]
+pub struct InlineMpz<const LIMBS: usize> {
+    // alloc is 0 while the value is stored inline
+    raw: UnsafeCell<mpz_t>,
+    limbs: [limb_t; LIMBS],
+}
+
+struct Scratch(Cell<Option<mpz_t>>);
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        if let Some(mut z) = self.0.take() {
+            unsafe {
+                mpz_clear(&mut z);
+            }
+        }
+    }
+}
+
+thread_local! {
+    static SCRATCH: Scratch = const { Scratch(Cell::new(None)) };
+}
+
+impl<const LIMBS: usize> InlineMpz<LIMBS> {
+    /// Creates an integer with a value of zero.
+    #[inline]
+    pub fn new() -> InlineMpz<LIMBS> {
+        InlineMpz {
+            raw: UnsafeCell::new(MPZ_ROINIT_N(ptr::null_mut(), 0)),
+            limbs: [0; LIMBS],
+        }
+    }
+
+    /// Returns [`true`] if the value is stored inline.
+    ///
+    /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        unsafe { (*self.raw.get()).alloc == 0 }
+    }
+
+    /// Returns a pointer to an [`mpz_t`] for reading, which is valid
+    /// until `self` is moved or mutated.
+    ///
+    /// The [`mpz_t`] must not be modified.
+    ///
+    /// [`mpz_t`]: struct.mpz_t.html
+    #[inline]
+    pub fn as_ptr(&self) -> *const mpz_t {
+        let raw = self.raw.get();
+        unsafe {
+            if (*raw).alloc == 0 {
+                let size = (*raw).size as size_t;
+                mpz_roinit_n(raw, self.limbs.as_ptr(), size)
+            } else {
+                raw
+            }
+        }
+    }
+
+    /// Calls `f` with a pointer to an initialized [`mpz_t`] holding
+    /// the value, which `f` can modify.
+    ///
+    /// The pointer can be used both as input and output, but it is
+    /// only valid inside `f`. `f` must leave the [`mpz_t`]
+    /// initialized, so it must not clear it or swap it with a
+    /// different [`mpz_t`].
+    ///
+    /// [`mpz_t`]: struct.mpz_t.html
+    pub fn with_mut<R, F: FnOnce(*mut mpz_t) -> R>(&mut self, f: F) -> R {
+        let raw = self.raw.get_mut();
+        if raw.alloc != 0 {
+            return f(raw);
+        }
+        unsafe {
+            let mut scratch = SCRATCH.with(|s| s.0.take()).unwrap_or_else(|| {
+                let mut z = MPZ_ROINIT_N(ptr::null_mut(), 0);
+                mpz_init2(&mut z, (LIMBS as c_ulong) * NUMB_BITS as c_ulong);
+                z
+            });
+            let size = raw.size as size_t;
+            let src = mpz_roinit_n(raw, self.limbs.as_ptr(), size);
+            mpz_set(&mut scratch, src);
+            let ret = f(&mut scratch);
+            let len = scratch.size.unsigned_abs() as usize;
+            if len <= LIMBS {
+                ptr::copy_nonoverlapping(scratch.d, self.limbs.as_mut_ptr(), len);
+                raw.size = scratch.size;
+                // if the scratch value is still in use by a nested call,
+                // keep the one with the larger allocation
+                SCRATCH.with(|s| {
+                    let keep = match s.0.take() {
+                        Some(other) if other.alloc >= scratch.alloc => {
+                            mpz_clear(&mut scratch);
+                            other
+                        }
+                        Some(mut other) => {
+                            mpz_clear(&mut other);
+                            scratch
+                        }
+                        None => scratch,
+                    };
+                    s.0.set(Some(keep));
+                });
+            } else {
+                *raw = scratch;
+            }
+            ret
+        }
+    }
+}
+
+impl<const LIMBS: usize> Default for InlineMpz<LIMBS> {
+    #[inline]
+    fn default() -> InlineMpz<LIMBS> {
+        InlineMpz::new()
+    }
+}
+
+impl<const LIMBS: usize> Clone for InlineMpz<LIMBS> {
+    fn clone(&self) -> InlineMpz<LIMBS> {
+        let mut ret = InlineMpz {
+            raw: UnsafeCell::new(unsafe { *self.raw.get() }),
+            limbs: self.limbs,
+        };
+        if !self.is_inline() {
+            unsafe {
+                mpz_init_set(ret.raw.get_mut(), self.as_ptr());
+            }
+        }
+        ret
+    }
+}
+
+impl<const LIMBS: usize> Drop for InlineMpz<LIMBS> {
+    #[inline]
+    fn drop(&mut self) {
+        if !self.is_inline() {
+            unsafe {
+                mpz_clear(self.raw.get_mut());
+            }
+        }
+    }
+}
 
 // Rational numbers
 
@@ -903,6 +1869,35 @@ extern "C" {
     /// See: [`mpq_clear`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Rational-Number-Functions.html#index-mpq_005fclear)
     #[link_name = "__gmpq_clear"]
     pub fn mpq_clear(x: mpq_ptr);
+}
+/// Initializes an [`mpq_t`] using [`mpq_init`] and returns it.
+///
+/// The returned value has to be freed using [`mpq_clear`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// let mut q = gmp::mpq_new();
+/// unsafe {
+///     gmp::mpq_set_si(&mut q, -1, 3);
+///     assert_eq!(gmp::mpq_sgn(&q), -1);
+///     gmp::mpq_clear(&mut q);
+/// }
+/// ```
+///
+/// [`mpq_clear`]: fn.mpq_clear.html
+/// [`mpq_init`]: fn.mpq_init.html
+/// [`mpq_t`]: struct.mpq_t.html
+#[inline]
+pub fn mpq_new() -> mpq_t {
+    let mut q = MaybeUninit::uninit();
+    unsafe {
+        mpq_init(q.as_mut_ptr());
+        q.assume_init()
+    }
+}
+extern "C" {
     /// See: [`mpq_clears`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Rational-Number-Functions.html#index-mpq_005fclears)
     #[link_name = "__gmpq_clears"]
     pub fn mpq_clears(x: mpq_ptr, ...);
@@ -939,6 +1934,140 @@ extern "C" {
     /// See: [`mpq_get_str`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Rational-Number-Functions.html#index-mpq_005fget_005fstr)
     #[link_name = "__gmpq_get_str"]
     pub fn mpq_get_str(str: *mut c_char, base: c_int, op: mpq_srcptr) -> *mut c_char;
+}
+/// Converts `op` to a [`String`] of the form `num/den` in base `base`,
+/// or of the form `num` if the denominator is one.
+///
+/// The buffer passed to [`mpq_get_str`] is allocated by Rust and
+/// sized using [`mpz_sizeinbase`] on the numerator and denominator,
+/// so there is no memory allocated by GMP to free.
+///
+/// # Safety
+///
+/// `op` must point to an initialized and canonicalized [`mpq_t`].
+///
+/// # Panics
+///
+/// Panics if `base` is not in the range 2 to 62 or −36 to −2.
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`mpq_get_str`]: fn.mpq_get_str.html
+/// [`mpq_t`]: struct.mpq_t.html
+/// [`mpz_sizeinbase`]: fn.mpz_sizeinbase.html
+pub unsafe fn mpq_get_string(base: c_int, op: mpq_srcptr) -> String {
+    misc::check_base(base);
+    let num = mpz_sizeinbase(mpq_numref_const(op), base.abs());
+    let den = mpz_sizeinbase(mpq_denref_const(op), base.abs());
+    // extra bytes for the sign, the slash and the nul terminator
+    let mut buf = Vec::<u8>::with_capacity(num + den + 3);
+    mpq_get_str(buf.as_mut_ptr() as *mut c_char, base, op);
+    misc::string_from_buf(buf)
+}
+
+/// Formats an [`mpq_t`] in decimal using [`mpq_get_str`], in the form
+/// `num/den`, or in the form `num` if the denominator is one.
+///
+/// The width, fill, alignment, sign and zero-padding options are
+/// supported. [`Debug`] formats the same as [`Display`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp::{self, DisplayMpq};
+/// let mut q = gmp::mpq_new();
+/// unsafe {
+///     gmp::mpq_set_si(&mut q, -3, 4);
+///     assert_eq!(format!("{}", DisplayMpq::new(&q)), "-3/4");
+///     gmp::mpq_set_si(&mut q, 5, 1);
+///     assert_eq!(format!("{:+}", DisplayMpq::new(&q)), "+5");
+///     gmp::mpq_clear(&mut q);
+/// }
+/// ```
+///
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`mpq_get_str`]: fn.mpq_get_str.html
+/// [`mpq_t`]: struct.mpq_t.html
+#[derive(Clone, Copy)]
+pub struct DisplayMpq<'a> {
+    q: &'a mpq_t,
+}
+
+impl<'a> DisplayMpq<'a> {
+    /// Creates an adapter that formats `q`.
+    ///
+    /// # Safety
+    ///
+    /// `q` must be initialized and canonicalized.
+    #[inline]
+    pub unsafe fn new(q: &'a mpq_t) -> DisplayMpq<'a> {
+        DisplayMpq { q }
+    }
+}
+
+impl<'a> Display for DisplayMpq<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let s = unsafe { mpq_get_string(10, self.q) };
+        match s.strip_prefix('-') {
+            Some(abs) => f.pad_integral(false, "", abs),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+impl<'a> Debug for DisplayMpq<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+macro_rules! mpq_try_get {
+    ($($name:ident($T:ident, $helper:ident);)*) => { $(
+        #[doc = concat!("Converts `op` to [`", stringify!($T), "`] if it is an integer.")]
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConversionError::NotInteger`] if the denominator of
+        /// `op` is not one, and [`ConversionError::Overflow`] if `op`
+        /// does not fit.
+        ///
+        /// # Safety
+        ///
+        /// `op` must point to an initialized and canonicalized
+        /// [`mpq_t`].
+        ///
+        /// [`ConversionError::NotInteger`]: enum.ConversionError.html#variant.NotInteger
+        /// [`ConversionError::Overflow`]: enum.ConversionError.html#variant.Overflow
+        #[doc = concat!(
+            "[`", stringify!($T), "`]: https://doc.rust-lang.org/std/primitive.",
+            stringify!($T), ".html"
+        )]
+        /// [`mpq_t`]: struct.mpq_t.html
+        #[inline]
+        pub unsafe fn $name(op: mpq_srcptr) -> Result<$T, ConversionError> {
+            if mpz_cmp_ui(mpq_denref_const(op), 1) != 0 {
+                return Err(ConversionError::NotInteger);
+            }
+            $helper(mpq_numref_const(op))
+        }
+    )* };
+}
+mpq_try_get! {
+    mpq_try_get_i8(i8, mpz_try_get_signed);
+    mpq_try_get_u8(u8, mpz_try_get_unsigned);
+    mpq_try_get_i16(i16, mpz_try_get_signed);
+    mpq_try_get_u16(u16, mpz_try_get_unsigned);
+    mpq_try_get_i32(i32, mpz_try_get_signed);
+    mpq_try_get_u32(u32, mpz_try_get_unsigned);
+    mpq_try_get_i64(i64, mpz_try_get_signed);
+    mpq_try_get_u64(u64, mpz_try_get_unsigned);
+    mpq_try_get_i128(i128, mpz_try_get_signed);
+    mpq_try_get_u128(u128, mpz_try_get_unsigned);
+    mpq_try_get_isize(isize, mpz_try_get_signed);
+    mpq_try_get_usize(usize, mpz_try_get_unsigned);
+}
+extern "C" {
 
     // Arithmetic Functions
 
@@ -1077,6 +2206,35 @@ extern "C" {
     /// See: [`mpf_init2`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Floating_002dpoint-Functions.html#index-mpf_005finit2)
     #[link_name = "__gmpf_init2"]
     pub fn mpf_init2(x: mpf_ptr, prec: bitcnt_t);
+}
+/// Initializes an [`mpf_t`] with precision `prec` using
+/// [`mpf_init2`] and returns it.
+///
+/// The returned value has to be freed using [`mpf_clear`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// let mut f = gmp::mpf_new2(100);
+/// unsafe {
+///     assert!(gmp::mpf_get_prec(&f) >= 100);
+///     gmp::mpf_clear(&mut f);
+/// }
+/// ```
+///
+/// [`mpf_clear`]: fn.mpf_clear.html
+/// [`mpf_init2`]: fn.mpf_init2.html
+/// [`mpf_t`]: struct.mpf_t.html
+#[inline]
+pub fn mpf_new2(prec: bitcnt_t) -> mpf_t {
+    let mut f = MaybeUninit::uninit();
+    unsafe {
+        mpf_init2(f.as_mut_ptr(), prec);
+        f.assume_init()
+    }
+}
+extern "C" {
     /// See: [`mpf_inits`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Floating_002dpoint-Functions.html#index-mpf_005finits)
     #[link_name = "__gmpf_inits"]
     pub fn mpf_inits(x: mpf_ptr, ...);
@@ -1164,6 +2322,131 @@ extern "C" {
         n_digits: usize,
         op: mpf_srcptr,
     ) -> *mut c_char;
+}
+/// Converts `op` to a [`String`] of significant digits in base `base`
+/// and returns it together with the exponent.
+///
+/// As with [`mpf_get_str`], there is an implicit radix point to the
+/// left of the first digit, and the returned string contains no
+/// radix point or exponent. If `n_digits` is zero, the maximum number
+/// of significant digits is generated.
+///
+/// If `n_digits` is not zero, the buffer is allocated by Rust.
+/// Otherwise the buffer is allocated by GMP, copied, and then freed
+/// using the current [free function].
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpf_t`].
+///
+/// # Panics
+///
+/// Panics if `base` is not in the range 2 to 62 or −36 to −2.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     gmp::mpf_init_set_d(&mut f, -12.75);
+///     assert_eq!(gmp::mpf_get_string(10, 0, &f), (String::from("-1275"), 2));
+///     assert_eq!(gmp::mpf_get_string(2, 2, &f), (String::from("-11"), 4));
+///     gmp::mpf_clear(&mut f);
+/// }
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`mpf_get_str`]: fn.mpf_get_str.html
+/// [`mpf_t`]: struct.mpf_t.html
+/// [free function]: fn.get_memory_functions.html
+pub unsafe fn mpf_get_string(base: c_int, n_digits: usize, op: mpf_srcptr) -> (String, exp_t) {
+    misc::check_base(base);
+    let mut exp = 0;
+    if n_digits != 0 {
+        // one extra byte for the sign and one for the nul terminator
+        let mut buf = Vec::<u8>::with_capacity(n_digits + 2);
+        mpf_get_str(
+            buf.as_mut_ptr() as *mut c_char,
+            &mut exp,
+            base,
+            n_digits,
+            op,
+        );
+        (misc::string_from_buf(buf), exp)
+    } else {
+        let s = mpf_get_str(ptr::null_mut(), &mut exp, base, 0, op);
+        let string = misc::string_from_ptr(s);
+        free_str(s);
+        (string, exp)
+    }
+}
+
+/// Formats an [`mpf_t`] in decimal using [`mpf_get_str`], in the same
+/// style as the [`LowerExp`] implementation of [`f64`].
+///
+/// The precision option sets the number of digits after the decimal
+/// point; without it, as many digits as are accurately representable
+/// are used, and trailing zeros are removed. The width, fill,
+/// alignment, sign and zero-padding options are also supported.
+/// [`Debug`] includes the precision of the number in bits.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp::{self, DisplayMpf};
+/// let mut f = gmp::mpf_new2(64);
+/// unsafe {
+///     gmp::mpf_set_d(&mut f, -12.75);
+///     let d = DisplayMpf::new(&f);
+///     assert_eq!(format!("{}", d), "-1.275e1");
+///     assert_eq!(format!("{:.1}", d), "-1.3e1");
+///     assert_eq!(format!("{:.5}", d), "-1.27500e1");
+///     assert_eq!(format!("{:?}", d), "mpf_t { prec: 64, value: -1.275e1 }");
+///     gmp::mpf_clear(&mut f);
+/// }
+/// ```
+///
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`LowerExp`]: https://doc.rust-lang.org/std/fmt/trait.LowerExp.html
+/// [`f64`]: https://doc.rust-lang.org/std/primitive.f64.html
+/// [`mpf_get_str`]: fn.mpf_get_str.html
+/// [`mpf_t`]: struct.mpf_t.html
+#[derive(Clone, Copy)]
+pub struct DisplayMpf<'a> {
+    f: &'a mpf_t,
+}
+
+impl<'a> DisplayMpf<'a> {
+    /// Creates an adapter that formats `f`.
+    ///
+    /// # Safety
+    ///
+    /// `f` must be initialized.
+    #[inline]
+    pub unsafe fn new(f: &'a mpf_t) -> DisplayMpf<'a> {
+        DisplayMpf { f }
+    }
+}
+
+impl<'a> Display for DisplayMpf<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let n_digits = f.precision().map_or(0, |p| p + 1);
+        let (s, exp) = unsafe { mpf_get_string(10, n_digits, self.f) };
+        misc::fmt_sci(f, &s, exp)
+    }
+}
+
+impl<'a> Debug for DisplayMpf<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("mpf_t")
+            .field("prec", &unsafe { mpf_get_prec(self.f) })
+            .field("value", &format_args!("{}", self))
+            .finish()
+    }
+}
+extern "C" {
 
     // Arithmetic Functions
 
@@ -1305,6 +2588,86 @@ extern "C" {
     pub fn mpf_random2(rop: mpf_ptr, max_size: size_t, exp: exp_t);
 }
 
+// Converts op using mpf_get_ui if it fits in a c_ulong, and through
+// an mpz_t otherwise.
+unsafe fn mpf_try_get_unsigned<T>(op: mpf_srcptr) -> Result<T, ConversionError>
+where
+    T: TryFrom<c_ulong> + TryFrom<u128>,
+{
+    if mpf_integer_p(op) == 0 {
+        Err(ConversionError::NotInteger)
+    } else if mpf_fits_ulong_p(op) != 0 {
+        T::try_from(mpf_get_ui(op)).map_err(|_| ConversionError::Overflow)
+    } else {
+        let mut z = mpz_new();
+        mpz_set_f(&mut z, op);
+        let ret = mpz_try_get_unsigned(&z);
+        mpz_clear(&mut z);
+        ret
+    }
+}
+
+// Converts op using mpf_get_si if it fits in a c_long, and through an
+// mpz_t otherwise.
+unsafe fn mpf_try_get_signed<T>(op: mpf_srcptr) -> Result<T, ConversionError>
+where
+    T: TryFrom<c_long> + TryFrom<i128>,
+{
+    if mpf_integer_p(op) == 0 {
+        Err(ConversionError::NotInteger)
+    } else if mpf_fits_slong_p(op) != 0 {
+        T::try_from(mpf_get_si(op)).map_err(|_| ConversionError::Overflow)
+    } else {
+        let mut z = mpz_new();
+        mpz_set_f(&mut z, op);
+        let ret = mpz_try_get_signed(&z);
+        mpz_clear(&mut z);
+        ret
+    }
+}
+
+macro_rules! mpf_try_get {
+    ($($name:ident($T:ident, $helper:ident);)*) => { $(
+        #[doc = concat!("Converts `op` to [`", stringify!($T), "`] if it is an integer.")]
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConversionError::NotInteger`] if `op` has a
+        /// fractional part, and [`ConversionError::Overflow`] if `op`
+        /// does not fit.
+        ///
+        /// # Safety
+        ///
+        /// `op` must point to an initialized [`mpf_t`].
+        ///
+        /// [`ConversionError::NotInteger`]: enum.ConversionError.html#variant.NotInteger
+        /// [`ConversionError::Overflow`]: enum.ConversionError.html#variant.Overflow
+        #[doc = concat!(
+            "[`", stringify!($T), "`]: https://doc.rust-lang.org/std/primitive.",
+            stringify!($T), ".html"
+        )]
+        /// [`mpf_t`]: struct.mpf_t.html
+        #[inline]
+        pub unsafe fn $name(op: mpf_srcptr) -> Result<$T, ConversionError> {
+            $helper(op)
+        }
+    )* };
+}
+mpf_try_get! {
+    mpf_try_get_i8(i8, mpf_try_get_signed);
+    mpf_try_get_u8(u8, mpf_try_get_unsigned);
+    mpf_try_get_i16(i16, mpf_try_get_signed);
+    mpf_try_get_u16(u16, mpf_try_get_unsigned);
+    mpf_try_get_i32(i32, mpf_try_get_signed);
+    mpf_try_get_u32(u32, mpf_try_get_unsigned);
+    mpf_try_get_i64(i64, mpf_try_get_signed);
+    mpf_try_get_u64(u64, mpf_try_get_unsigned);
+    mpf_try_get_i128(i128, mpf_try_get_signed);
+    mpf_try_get_u128(u128, mpf_try_get_unsigned);
+    mpf_try_get_isize(isize, mpf_try_get_signed);
+    mpf_try_get_usize(usize, mpf_try_get_unsigned);
+}
+
 // Low-Level Functions
 
 extern "C" {
@@ -1629,6 +2992,36 @@ extern "C" {
     /// See: [`gmp_randclear`](https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Random-Number-Functions.html#index-gmp_005frandclear)
     #[link_name = "__gmp_randclear"]
     pub fn randclear(state: randstate_ptr);
+}
+/// Initializes a [`randstate_t`] for the Mersenne Twister algorithm
+/// using [`randinit_mt`] and returns it.
+///
+/// The returned value has to be freed using [`randclear`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// let mut state = gmp::randstate_new_mt();
+/// unsafe {
+///     gmp::randseed_ui(&mut state, 42);
+///     assert!(gmp::urandomm_ui(&mut state, 10) < 10);
+///     gmp::randclear(&mut state);
+/// }
+/// ```
+///
+/// [`randclear`]: fn.randclear.html
+/// [`randinit_mt`]: fn.randinit_mt.html
+/// [`randstate_t`]: struct.randstate_t.html
+#[inline]
+pub fn randstate_new_mt() -> randstate_t {
+    let mut state = MaybeUninit::uninit();
+    unsafe {
+        randinit_mt(state.as_mut_ptr());
+        state.assume_init()
+    }
+}
+extern "C" {
 
     // Random State Seeding
 
@@ -1709,10 +3102,21 @@ extern "C" {
     );
 }
 
+// Frees a nul-terminated string allocated by GMP, such as one
+// returned by mpf_get_str when passed a null pointer, or by asprintf.
+pub(crate) unsafe fn free_str(s: *mut c_char) {
+    let mut free = None;
+    get_memory_functions(ptr::null_mut(), ptr::null_mut(), &mut free);
+    let free = free.expect("no GMP free function");
+    let size = CStr::from_ptr(s).to_bytes().len() + 1;
+    free(s as *mut c_void, size);
+}
+
 #[cfg(test)]
 mod tests {
     use gmp;
     use std::mem;
+    use std::os::raw::c_int;
 
     #[test]
     fn check_mpq_num_den_offsets() {
@@ -1746,11 +3150,211 @@ mod tests {
         assert_eq!(from_static, from_constant);
     }
 
-    #[cfg(not(newer_cache))]
+    #[test]
+    fn check_get_string() {
+        unsafe {
+            let mut q = mem::zeroed();
+            gmp::mpq_init(&mut q);
+            gmp::mpq_set_si(&mut q, -1, 36);
+            assert_eq!(gmp::mpq_get_string(36, &q), "-1/10");
+            assert_eq!(gmp::mpz_get_string(62, gmp::mpq_denref_const(&q)), "a");
+            gmp::mpq_clear(&mut q);
+
+            let mut f = mem::zeroed();
+            gmp::mpf_init_set_si(&mut f, 0);
+            assert_eq!(gmp::mpf_get_string(10, 0, &f), (String::new(), 0));
+            gmp::mpf_set_d(&mut f, 0.0625);
+            assert_eq!(gmp::mpf_get_string(10, 0, &f), (String::from("625"), -1));
+            assert_eq!(gmp::mpf_get_string(10, 2, &f), (String::from("63"), -1));
+            gmp::mpf_clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_display() {
+        use gmp::{DisplayMpf, DisplayMpq, DisplayMpz};
+        unsafe {
+            let mut z = gmp::mpz_new();
+            assert_eq!(format!("{}", DisplayMpz::new(&z)), "0");
+            gmp::mpz_set_si(&mut z, 42);
+            assert_eq!(format!("{:+}", DisplayMpz::new(&z)), "+42");
+            assert_eq!(format!("{:<5}|", DisplayMpz::new(&z)), "42   |");
+            assert_eq!(format!("{:?}", DisplayMpz::new(&z)), "42");
+            gmp::mpz_clear(&mut z);
+
+            let mut q = gmp::mpq_new();
+            gmp::mpq_set_si(&mut q, -6, 4);
+            gmp::mpq_canonicalize(&mut q);
+            assert_eq!(format!("{:>6}", DisplayMpq::new(&q)), "  -3/2");
+            assert_eq!(format!("{:06}", DisplayMpq::new(&q)), "-003/2");
+            gmp::mpq_clear(&mut q);
+
+            let mut f = gmp::mpf_new2(64);
+            assert_eq!(format!("{}", DisplayMpf::new(&f)), "0e0");
+            assert_eq!(format!("{:.2}", DisplayMpf::new(&f)), "0.00e0");
+            gmp::mpf_set_d(&mut f, 0.0625);
+            assert_eq!(format!("{}", DisplayMpf::new(&f)), "6.25e-2");
+            assert_eq!(format!("{:.0}", DisplayMpf::new(&f)), "6e-2");
+            assert_eq!(format!("{:+10.3}", DisplayMpf::new(&f)), " +6.250e-2");
+            gmp::mpf_set_d(&mut f, -1e10);
+            assert_eq!(format!("{}", DisplayMpf::new(&f)), "-1e10");
+            gmp::mpf_clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_128_bit() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            for &u in &[
+                0,
+                1,
+                u128::from(u64::MAX),
+                u128::from(u64::MAX) + 1,
+                u128::MAX,
+            ] {
+                gmp::mpz_set_u128(&mut z, u);
+                assert_eq!(gmp::mpz_get_string(10, &z), u.to_string());
+                assert_eq!(gmp::mpz_get_u128(&z), u);
+                assert_eq!(gmp::mpz_fits_u128_p(&z), 1);
+            }
+            for &i in &[i128::MIN, i128::MIN + 1, -1, 0, i128::MAX] {
+                gmp::mpz_set_i128(&mut z, i);
+                assert_eq!(gmp::mpz_get_string(10, &z), i.to_string());
+                assert_eq!(gmp::mpz_get_i128(&z), i);
+                assert_eq!(gmp::mpz_fits_i128_p(&z), 1);
+                assert_eq!(gmp::mpz_fits_u128_p(&z), (i >= 0) as c_int);
+            }
+            // 2^128 fits in neither
+            gmp::mpz_set_u128(&mut z, u128::MAX);
+            gmp::mpz_add_ui(&mut z, &z, 1);
+            assert_eq!(gmp::mpz_fits_u128_p(&z), 0);
+            assert_eq!(gmp::mpz_fits_i128_p(&z), 0);
+            assert_eq!(gmp::mpz_get_u128(&z), 0);
+            // −2^127 − 1 does not fit in i128 and wraps around
+            gmp::mpz_set_i128(&mut z, i128::MIN);
+            gmp::mpz_sub_ui(&mut z, &z, 1);
+            assert_eq!(gmp::mpz_fits_i128_p(&z), 0);
+            assert_eq!(gmp::mpz_get_i128(&z), i128::MAX);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+
+    #[test]
+    fn check_try_get() {
+        use gmp::ConversionError::*;
+        unsafe {
+            let mut z = gmp::mpz_new();
+            assert_eq!(gmp::mpz_try_get_u8(&z), Ok(0));
+            assert_eq!(gmp::mpz_try_get_i128(&z), Ok(0));
+            gmp::mpz_set_ui(&mut z, 255);
+            assert_eq!(gmp::mpz_try_get_u8(&z), Ok(255));
+            assert_eq!(gmp::mpz_try_get_i8(&z), Err(Overflow));
+            assert_eq!(gmp::mpz_try_get_i16(&z), Ok(255));
+            gmp::mpz_set_si(&mut z, -1);
+            assert_eq!(gmp::mpz_try_get_i8(&z), Ok(-1));
+            assert_eq!(gmp::mpz_try_get_isize(&z), Ok(-1));
+            assert_eq!(gmp::mpz_try_get_u64(&z), Err(Overflow));
+            assert_eq!(gmp::mpz_try_get_usize(&z), Err(Overflow));
+            gmp::mpz_set_u128(&mut z, u128::from(u64::MAX) + 1);
+            assert_eq!(gmp::mpz_try_get_u64(&z), Err(Overflow));
+            assert_eq!(gmp::mpz_try_get_u128(&z), Ok(u128::from(u64::MAX) + 1));
+            assert_eq!(gmp::mpz_try_get_i128(&z), Ok(i128::from(u64::MAX) + 1));
+            gmp::mpz_set_i128(&mut z, i128::MIN);
+            assert_eq!(gmp::mpz_try_get_i128(&z), Ok(i128::MIN));
+            assert_eq!(gmp::mpz_try_get_i64(&z), Err(Overflow));
+            gmp::mpz_sub_ui(&mut z, &z, 1);
+            assert_eq!(gmp::mpz_try_get_i128(&z), Err(Overflow));
+            gmp::mpz_set_u128(&mut z, u128::MAX);
+            assert_eq!(gmp::mpz_try_get_u128(&z), Ok(u128::MAX));
+            assert_eq!(gmp::mpz_try_get_i128(&z), Err(Overflow));
+            gmp::mpz_clear(&mut z);
+
+            let mut q = gmp::mpq_new();
+            gmp::mpq_set_si(&mut q, 7, 2);
+            assert_eq!(gmp::mpq_try_get_i32(&q), Err(NotInteger));
+            gmp::mpq_set_si(&mut q, -6, 3);
+            gmp::mpq_canonicalize(&mut q);
+            assert_eq!(gmp::mpq_try_get_i8(&q), Ok(-2));
+            assert_eq!(gmp::mpq_try_get_u8(&q), Err(Overflow));
+            gmp::mpq_clear(&mut q);
+
+            let mut f = gmp::mpf_new2(128);
+            gmp::mpf_set_d(&mut f, 2.5);
+            assert_eq!(gmp::mpf_try_get_u32(&f), Err(NotInteger));
+            gmp::mpf_set_d(&mut f, -2f64.powi(70));
+            assert_eq!(gmp::mpf_try_get_i128(&f), Ok(-1 << 70));
+            assert_eq!(gmp::mpf_try_get_i64(&f), Err(Overflow));
+            assert_eq!(gmp::mpf_try_get_u128(&f), Err(Overflow));
+            gmp::mpf_set_si(&mut f, 40000);
+            assert_eq!(gmp::mpf_try_get_u16(&f), Ok(40000));
+            assert_eq!(gmp::mpf_try_get_i16(&f), Err(Overflow));
+            gmp::mpf_clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_words() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            let bytes_le = [0xefu8, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01, 0xff, 0, 0];
+            gmp::mpz_set_words_le(&mut z, &bytes_le);
+            assert_eq!(gmp::mpz_get_string(16, &z), "ff0123456789abcdef");
+            let mut bytes_be = bytes_le;
+            bytes_be.reverse();
+            gmp::mpz_set_words_be(&mut z, &bytes_be);
+            assert_eq!(gmp::mpz_get_string(16, &z), "ff0123456789abcdef");
+
+            let mut u16s = [0xffffu16; 6];
+            assert_eq!(gmp::mpz_get_words_le(&mut u16s, &z), 5);
+            assert_eq!(u16s, [0xcdef, 0x89ab, 0x4567, 0x0123, 0x00ff, 0]);
+            let mut u32s = [0u32; 2];
+            assert_eq!(gmp::mpz_get_words_be(&mut u32s, &z), 3);
+            assert_eq!(u32s, [0x0123_4567, 0x89ab_cdef]);
+            let mut u64s = [0u64; 2];
+            assert_eq!(gmp::mpz_get_words_le(&mut u64s, &z), 2);
+            gmp::mpz_set_words_le(&mut z, &u64s);
+            assert_eq!(gmp::mpz_get_string(16, &z), "ff0123456789abcdef");
+
+            gmp::mpz_set_words_le::<u32>(&mut z, &[]);
+            assert_eq!(gmp::mpz_sgn(&z), 0);
+            assert_eq!(gmp::mpz_words_len::<u8>(&z), 0);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+
+    #[test]
+    fn check_mpz_const() {
+        const ZERO: gmp::mpz_t = mpz_const!("-0");
+        const HEX: gmp::mpz_t = mpz_const!("-0x1234_5678_9abc_def0_0fed_cba9_8765_4321");
+        const BIN: gmp::mpz_t = mpz_const!("0b1011");
+        const OCT: gmp::mpz_t = mpz_const!("0777");
+        const DEC: gmp::mpz_t = mpz_const!("340282366920938463463374607431768211455");
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            for &(c, s) in &[
+                (ZERO, "0"),
+                (HEX, "-0x1234_5678_9abc_def0_0fed_cba9_8765_4321"),
+                (BIN, "11"),
+                (OCT, "511"),
+                (DEC, "340282366920938463463374607431768211455"),
+            ] {
+                let s = format!("{}\0", s.replace('_', ""));
+                gmp::mpz_set_str(&mut z, s.as_ptr() as _, 0);
+                assert_eq!(gmp::mpz_cmp(&c, &z), 0);
+                assert_eq!(c.alloc, 0);
+            }
+            assert_eq!(gmp::mpz_sizeinbase(&DEC, 2), 128);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+
     #[test]
     fn check_version() {
         use std::ffi::CStr;
-        let version = "6.1.2";
         let from_static = unsafe { CStr::from_ptr(gmp::version) };
         let from_constants = format!(
             "{}.{}.{}",
@@ -1758,7 +3362,155 @@ mod tests {
             gmp::VERSION_MINOR,
             gmp::VERSION_PATCHLEVEL
         );
-        assert_eq!(from_static.to_str().unwrap(), version);
-        assert_eq!(from_constants, version);
+        assert_eq!(from_static.to_str().unwrap(), from_constants);
+        assert!((gmp::VERSION, gmp::VERSION_MINOR) >= (6, 1));
+        assert_eq!(cfg!(gmp_6_2), (gmp::VERSION, gmp::VERSION_MINOR) >= (6, 2));
+        assert_eq!(cfg!(gmp_6_3), (gmp::VERSION, gmp::VERSION_MINOR) >= (6, 3));
+    }
+
+    #[test]
+    fn check_inline_mpz() {
+        use gmp::InlineMpz;
+
+        let mut plain = unsafe {
+            let mut plain = mem::zeroed();
+            gmp::mpz_init(&mut plain);
+            plain
+        };
+        let pp: *mut gmp::mpz_t = &mut plain;
+        let mut inline = InlineMpz::<2>::new();
+        assert_eq!(unsafe { gmp::mpz_sgn(inline.as_ptr()) }, 0);
+        let mut other = InlineMpz::<2>::new();
+        other.with_mut(|o| unsafe { gmp::mpz_set_si(o, -12345) });
+
+        // grow past two limbs
+        let mut spilled = false;
+        for i in 0..40 {
+            inline.with_mut(|z| unsafe {
+                gmp::mpz_mul_si(z, z, -1_000_003);
+                gmp::mpz_sub(z, z, other.as_ptr());
+            });
+            unsafe {
+                gmp::mpz_mul_si(pp, pp, -1_000_003);
+                gmp::mpz_sub(pp, pp, other.as_ptr());
+                assert_eq!(gmp::mpz_cmp(inline.as_ptr(), pp), 0, "{}", i);
+                spilled |= gmp::mpz_size(pp) > 2;
+            }
+            assert_eq!(inline.is_inline(), !spilled, "{}", i);
+            if i == 10 {
+                // move the value around while it is inline
+                let moved = vec![inline];
+                inline = moved.into_iter().next().unwrap();
+            }
+        }
+        let copy = inline.clone();
+        inline.with_mut(|z| unsafe { gmp::mpz_tdiv_q_2exp(z, z, 900) });
+        unsafe {
+            gmp::mpz_tdiv_q_2exp(pp, pp, 900);
+            assert_eq!(gmp::mpz_cmp(inline.as_ptr(), pp), 0);
+            assert_ne!(gmp::mpz_cmp(copy.as_ptr(), pp), 0);
+            gmp::mpz_clear(pp);
+        }
+
+        // nested writes each get their own scratch value
+        let mut a = InlineMpz::<1>::new();
+        let mut b = InlineMpz::<1>::new();
+        a.with_mut(|az| unsafe {
+            gmp::mpz_set_ui(az, 6);
+            b.with_mut(|bz| gmp::mpz_mul(bz, az, az));
+            gmp::mpz_add_ui(az, az, 1);
+        });
+        unsafe {
+            assert_eq!(gmp::mpz_cmp_ui(a.as_ptr(), 7), 0);
+            assert_eq!(gmp::mpz_cmp_ui(b.as_ptr(), 36), 0);
+        }
+        assert!(a.is_inline() && b.is_inline());
+    }
+
+    #[test]
+    fn check_threads() {
+        use std::thread;
+        fn send_sync<T: Send + Sync>() {}
+        fn send<T: Send>() {}
+        send_sync::<gmp::mpz_t>();
+        send_sync::<gmp::mpq_t>();
+        send_sync::<gmp::mpf_t>();
+        send::<gmp::randstate_t>();
+        unsafe {
+            // allocated in one thread, used and cleared in another
+            let mut z = thread::spawn(|| {
+                let mut z = gmp::mpz_new();
+                gmp::mpz_ui_pow_ui(&mut z, 2, 100);
+                z
+            })
+            .join()
+            .unwrap();
+            let s = thread::scope(|scope| {
+                let zr = &z;
+                let handles = (0..4)
+                    .map(|_| scope.spawn(move || gmp::mpz_get_string(16, zr)))
+                    .collect::<Vec<_>>();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap())
+                    .collect::<Vec<_>>()
+            });
+            assert!(s.iter().all(|s| *s == format!("1{}", "0".repeat(25))));
+            thread::spawn(move || {
+                let zp: *mut gmp::mpz_t = &mut z;
+                gmp::mpz_add_ui(zp, zp, 1);
+                assert_eq!(gmp::mpz_sizeinbase(zp, 2), 101);
+                gmp::mpz_clear(&mut z);
+            })
+            .join()
+            .unwrap();
+
+            let mut q = gmp::mpq_new();
+            gmp::mpq_set_si(&mut q, -1, 3);
+            let mut f = gmp::mpf_new2(64);
+            gmp::mpf_set_q(&mut f, &q);
+            thread::spawn(move || {
+                assert_eq!(gmp::mpq_cmp_si(&q, -1, 3), 0);
+                assert!(gmp::mpf_cmp_d(&f, -0.3334) > 0);
+                assert!(gmp::mpf_cmp_d(&f, -0.3333) < 0);
+                gmp::mpq_clear(&mut q);
+                gmp::mpf_clear(&mut f);
+            })
+            .join()
+            .unwrap();
+
+            // a random state can be moved to another thread and continue
+            // its sequence there
+            let mut state = gmp::randstate_new_mt();
+            let mut copy = mem::zeroed();
+            gmp::randinit_set(&mut copy, &state);
+            let first = gmp::urandomb_ui(&mut copy, 32);
+            let second = gmp::urandomb_ui(&mut copy, 32);
+            gmp::randclear(&mut copy);
+            assert_eq!(gmp::urandomb_ui(&mut state, 32), first);
+            let (mut state, next) = thread::spawn(move || {
+                let next = gmp::urandomb_ui(&mut state, 32);
+                (state, next)
+            })
+            .join()
+            .unwrap();
+            assert_eq!(next, second);
+            gmp::randclear(&mut state);
+        }
+    }
+
+    #[cfg(gmp_6_3)]
+    #[test]
+    fn check_prevprime() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init_set_ui(&mut z, 100);
+            let zp: *mut gmp::mpz_t = &mut z;
+            assert_eq!(gmp::mpz_prevprime(zp, zp), 2);
+            assert_eq!(gmp::mpz_cmp_ui(&z, 97), 0);
+            gmp::mpz_set_ui(&mut z, 2);
+            assert_eq!(gmp::mpz_prevprime(zp, zp), 0);
+            gmp::mpz_clear(&mut z);
+        }
     }
 }
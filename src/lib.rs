@@ -40,6 +40,16 @@ This crate contains three modules:
   * [`mpfr`] provides external FFI bindings to [MPFR].
   * [`mpc`] provides external FFI bindings to [MPC].
 
+On GNU/Linux, there is also the [`file`] module, which provides
+adapters from Rust readers and writers to the C `FILE` streams
+expected by functions such as [`gmp::mpz_out_str`].
+
+The [`format`] module provides the [`gmp_format!`] macro, which
+formats numbers like [`gmp::asprintf`] but checks the conversion
+specifications against the argument types at compile time. Similarly,
+the [`scan`] module provides the [`gmp_sscanf!`] and [`gmp_fscanf!`]
+macros for type-checked formatted input.
+
 If you want a high-level API, consider using [Rug][rug crate], a crate
 which provides integers and floating-point numbers with arbitrary
 precision and correct rounding:
@@ -93,7 +103,7 @@ extern crate gmp_mpfr_sys;
 # fn main() {}
 ```
 
-This crate required rustc version 1.13.0 or later.
+This crate requires rustc version 1.73.0 or later.
 
 If the C libraries have a major version bump with some deprecated
 functions removed, but no features are removed in the Rust bindings,
@@ -107,18 +117,55 @@ header (*.h*) and library (*.a*) files built using C, it can be a good
 idea to depend on version `"~1.1"` instead of version `"1.1"` in order
 to ensure backwards compatibility at the C level as well.
 
+## Thread safety
+
+The raw types [`gmp::mpz_t`], [`gmp::mpq_t`], [`gmp::mpf_t`],
+[`mpfr::mpfr_t`] and [`mpc::mpc_t`] implement [`Send`] and [`Sync`],
+so values can be moved between threads, for example to a worker pool,
+and the same value can be read from several threads at once as long as
+no thread modifies it. [`gmp::randstate_t`] implements [`Send`] only.
+The C libraries are thread-safe as long as distinct threads do not
+modify the same object, but some global state is either shared by all
+threads or kept separately for each thread:
+
+  * The memory functions set using [`gmp::set_memory_functions`] are
+    process-wide. They should be set before other threads start using
+    the libraries.
+  * The default precision of `mpf_t` values, set using
+    `gmp::mpf_set_default_prec`, is process-wide and is not
+    synchronized, so it should not be changed while other threads
+    could be initializing `mpf_t` values.
+  * MPFR is built with thread-safe support, so its default precision,
+    default rounding mode, exponent range, exception flags and caches
+    of constants are thread-local. A value moved to another thread
+    keeps its precision, but operations on it use the exponent range
+    and flags of the new thread. MPC has no global state of its own
+    and uses the MPFR state of the current thread.
+
 ## Optional features
 
-The gmp-mpfr-sys crate has two optional features:
+The gmp-mpfr-sys crate has the following optional features:
 
  1. `mpfr`, enabled by default. Required to include the [MPFR]
     library.
  2. `mpc`, enabled by default. Required to include the [MPC] library.
     This feature requires the `mpfr` feature.
+ 3. `float128`, disabled by default. Builds MPFR with support for the
+    `__float128` type and adds the functions `mpfr::set_float128` and
+    `mpfr::get_float128`. This feature requires the `mpfr` feature
+    and a C compiler that supports `__float128`.
+ 4. `decimal-float`, disabled by default. Builds MPFR with support
+    for decimal floating-point numbers and adds the functions
+    `mpfr::set_decimal64` and `mpfr::get_decimal64`. This feature
+    requires the `mpfr` feature and a C compiler that supports
+    `_Decimal64`.
+ 5. `owned`, disabled by default. Adds the `owned` module with thin
+    wrappers that own GMP, MPFR and MPC values, clear them when
+    dropped and clone them using the initialize-and-set functions.
 
 The [GMP] library is always included.
 
-The two optional features are enabled by default; to use features
+The `mpfr` and `mpc` features are enabled by default; to use features
 selectively, you can add the dependency like this to [*Cargo.toml*]:
 
 ```toml
@@ -227,21 +274,31 @@ To use a different directory, you can set the environment variable
 [`Integer`]: https://docs.rs/rug/&#42;/rug/struct.Integer.html
 [`MPFR_RNDN`]: https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#Rounding-Modes
 [`Rational`]: https://docs.rs/rug/&#42;/rug/struct.Rational.html
+[`Send`]: https://doc.rust-lang.org/std/marker/trait.Send.html
+[`Sync`]: https://doc.rust-lang.org/std/marker/trait.Sync.html
 [`enum MPFR_RND_T`]: https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft
+[`file`]: file/index.html
+[`format`]: format/index.html
+[`gmp::asprintf`]: gmp/fn.asprintf.html
 [`gmp::mpf_t`]: gmp/struct.mpf_t.html
 [`gmp::mpq_t`]: gmp/struct.mpq_t.html
 [`gmp::mpz_init`]: gmp/fn.mpz_init.html
+[`gmp::mpz_out_str`]: gmp/fn.mpz_out_str.html
 [`gmp::mpz_t`]: gmp/struct.mpz_t.html
 [`gmp::randstate_t`]: gmp/struct.randstate_t.html
 [`gmp::set_memory_functions`]: gmp/fn.set_memory_functions.html
 [`gmp`]: gmp/index.html
+[`gmp_format!`]: macro.gmp_format.html
+[`gmp_fscanf!`]: macro.gmp_fscanf.html
+[`gmp_sscanf!`]: macro.gmp_sscanf.html
 [`mp_set_memory_functions`]: https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Custom-Allocation.html#index-mp_005fset_005fmemory_005ffunctions
 [`mpc::mpc_t`]: mpc/struct.mpc_t.html
 [`mpc`]: mpc/index.html
 [`mpfr::mpfr_t`]: mpfr/struct.mpfr_t.html
-[`mpfr::rnd_t::RNDN`]: mpfr/enum.rnd_t.html#variant.RNDN
+[`mpfr::rnd_t::RNDN`]: mpfr/struct.rnd_t.html#associatedconstant.RNDN
 [`mpfr`]: mpfr/index.html
 [`mpz_init`]: https://tspiteri.gitlab.io/gmp-mpfr-sys/gmp/Integer-Functions.html#index-mpz_005finit
+[`scan`]: scan/index.html
 [msys]:     https://msys2.github.io/
 [rug crate]: https://crates.io/crates/rug
 [sys crate]: https://crates.io/crates/gmp-mpfr-sys
@@ -251,18 +308,25 @@ To use a different directory, you can set the environment variable
 #![doc(html_logo_url = "https://tspiteri.gitlab.io/gmp-mpfr-sys/rug.svg")]
 #![doc(test(attr(deny(warnings))))]
 #![cfg_attr(feature = "fail-on-warnings", deny(warnings))]
-#![cfg_attr(nightly_maybe_uninit, feature(maybe_uninit))]
 
 extern crate libc;
 
+#[cfg(target_os = "linux")]
+pub mod file;
+pub mod format;
 pub mod gmp;
 #[cfg(feature = "mpc")]
 pub mod mpc;
 #[cfg(feature = "mpfr")]
 pub mod mpfr;
+#[cfg(feature = "owned")]
+pub mod owned;
+pub mod scan;
 
 mod misc {
-    use std::os::raw::{c_int, c_long};
+    use std::ffi::CStr;
+    use std::fmt::{Formatter, Result as FmtResult};
+    use std::os::raw::{c_char, c_int, c_long};
 
     #[cfg(any(target_pointer_width = "32", windows))]
     #[inline]
@@ -275,4 +339,86 @@ mod misc {
     pub fn int_to_long(i: c_int) -> c_long {
         i.into()
     }
+
+    // Checks the base accepted by the GMP and MPFR string conversion
+    // functions.
+    pub fn check_base(base: c_int) {
+        assert!(
+            (2..=62).contains(&base) || (-36..=-2).contains(&base),
+            "base out of range"
+        );
+    }
+
+    // Converts a buffer into which a C function has written a
+    // nul-terminated ASCII string into a String without copying.
+    pub unsafe fn string_from_buf(mut buf: Vec<u8>) -> String {
+        let len = CStr::from_ptr(buf.as_ptr() as *const c_char)
+            .to_bytes()
+            .len();
+        buf.set_len(len);
+        String::from_utf8_unchecked(buf)
+    }
+
+    // Copies a nul-terminated ASCII string allocated by a C function
+    // into a String; the caller remains responsible for freeing s.
+    pub unsafe fn string_from_ptr(s: *const c_char) -> String {
+        String::from_utf8_unchecked(CStr::from_ptr(s).to_bytes().to_vec())
+    }
+
+    // Pads a string according to the width, fill and alignment of
+    // the formatter. Unlike Formatter::pad, the precision does not
+    // truncate the string.
+    #[cfg(feature = "mpfr")]
+    pub fn pad_str(f: &mut Formatter, s: &str) -> FmtResult {
+        use std::fmt::{Alignment, Write};
+
+        let pad = f.width().map_or(0, |w| w.saturating_sub(s.chars().count()));
+        let (pre, post) = match f.align() {
+            Some(Alignment::Right) => (pad, 0),
+            Some(Alignment::Center) => (pad / 2, pad - pad / 2),
+            _ => (0, pad),
+        };
+        let fill = f.fill();
+        for _ in 0..pre {
+            f.write_char(fill)?;
+        }
+        f.write_str(s)?;
+        for _ in 0..post {
+            f.write_char(fill)?;
+        }
+        Ok(())
+    }
+
+    // Formats the digits and exponent returned by the mpf and mpfr
+    // get_str functions in the same style as LowerExp for f64. If no
+    // precision is requested, trailing zeros are removed; otherwise
+    // the digits are padded to precision + 1 significant digits.
+    pub fn fmt_sci(f: &mut Formatter, digits: &str, exp: c_long) -> FmtResult {
+        let (nonneg, digits) = match digits.strip_prefix('-') {
+            Some(abs) => (false, abs),
+            None => (true, digits),
+        };
+        let mut digits = String::from(match f.precision() {
+            Some(_) => digits,
+            None => digits.trim_end_matches('0'),
+        });
+        let exp = if digits.is_empty() { 0 } else { exp - 1 };
+        if digits.is_empty() {
+            digits.push('0');
+        }
+        if let Some(prec) = f.precision() {
+            while digits.len() < prec + 1 {
+                digits.push('0');
+            }
+        }
+        let mut body = String::with_capacity(digits.len() + 24);
+        body.push_str(&digits[..1]);
+        if digits.len() > 1 {
+            body.push('.');
+            body.push_str(&digits[1..]);
+        }
+        body.push('e');
+        body.push_str(&exp.to_string());
+        f.pad_integral(nonneg, "", &body)
+    }
 }
@@ -20,17 +20,13 @@ Function and type bindings for the [MPC] library.
 # Examples
 
 ```rust
-# #[cfg(all(maybe_uninit, not(nightly_maybe_uninit)))] {
 use gmp_mpfr_sys::mpc;
 use gmp_mpfr_sys::mpfr;
 use std::f64;
-use std::mem::MaybeUninit;
 let one_third = 1.0_f64 / 3.0;
 let neg_inf = f64::NEG_INFINITY;
+let mut c = mpc::new3(53, 53);
 unsafe {
-    let mut c = MaybeUninit::uninit();
-    mpc::init3(c.as_mut_ptr(), 53, 53);
-    let mut c = c.assume_init();
     let dirs = mpc::set_d_d(&mut c, one_third, neg_inf, mpc::RNDNN);
     assert_eq!(dirs, 0);
     let re_ptr = mpc::realref_const(&c);
@@ -40,7 +36,6 @@ unsafe {
     let im = mpfr::get_d(im_ptr, mpfr::rnd_t::RNDN);
     assert_eq!(im, neg_inf);
     mpc::clear(&mut c);
-# }
 }
 ```
 
@@ -50,7 +45,11 @@ unsafe {
 
 use gmp;
 use libc::{intmax_t, uintmax_t, FILE};
+use misc;
 use mpfr;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::mem::MaybeUninit;
 use std::os::raw::{c_char, c_int, c_long, c_ulong};
 
 #[inline]
@@ -82,13 +81,144 @@ pub extern "C" fn INEX2(inex: c_int) -> c_int {
     inex >> 4
 }
 
+#[inline]
+fn ordering_to_pos(ord: Ordering) -> c_int {
+    match ord {
+        Ordering::Less => 2,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// The ternary value of a function that returns a complex number,
+/// decoded into the ternary values of its real and imaginary parts.
+///
+/// Each part is [`Less`] if the rounded value is less than the exact
+/// value, [`Equal`] if the rounded value is exact, and [`Greater`] if
+/// the rounded value is greater than the exact value.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpc::{self, Inexact};
+/// use std::cmp::Ordering;
+/// use std::mem;
+/// unsafe {
+///     let mut c = mem::zeroed();
+///     mpc::init2(&mut c, 2);
+///     // 7 rounds down to 6 and 9 rounds up to 12
+///     let inex = Inexact::from_ternary(mpc::set_ui_ui(&mut c, 7, 9, mpc::RNDDU));
+///     assert_eq!(inex.re, Ordering::Less);
+///     assert_eq!(inex.im, Ordering::Greater);
+///     assert!(!inex.is_exact());
+///     mpc::clear(&mut c);
+/// }
+/// ```
+///
+/// [`Equal`]: https://doc.rust-lang.org/std/cmp/enum.Ordering.html#variant.Equal
+/// [`Greater`]: https://doc.rust-lang.org/std/cmp/enum.Ordering.html#variant.Greater
+/// [`Less`]: https://doc.rust-lang.org/std/cmp/enum.Ordering.html#variant.Less
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Inexact {
+    /// The ternary value of the real part.
+    pub re: Ordering,
+    /// The ternary value of the imaginary part.
+    pub im: Ordering,
+}
+
+impl Inexact {
+    /// Decodes the ternary value returned by a function.
+    ///
+    /// See: [Return Value](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#return_002dvalue)
+    #[inline]
+    pub fn from_ternary(inex: c_int) -> Inexact {
+        Inexact {
+            re: INEX_RE(inex).cmp(&0),
+            im: INEX_IM(inex).cmp(&0),
+        }
+    }
+
+    /// Encodes the ternary value as returned by a function.
+    ///
+    /// See: [Return Value](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#return_002dvalue)
+    #[inline]
+    pub fn to_ternary(self) -> c_int {
+        ordering_to_pos(self.re) | (ordering_to_pos(self.im) << 2)
+    }
+
+    /// Returns [`true`] if both parts are exact.
+    ///
+    /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
+    #[inline]
+    pub fn is_exact(self) -> bool {
+        self.re == Ordering::Equal && self.im == Ordering::Equal
+    }
+}
+
+/// The ternary value of a function that returns two complex numbers,
+/// such as [`sin_cos`] and [`fma`], decoded into two [`Inexact`]
+/// values.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpc::{self, InexactPair};
+/// use std::mem;
+/// unsafe {
+///     let (mut s, mut c, mut z) = (mem::zeroed(), mem::zeroed(), mem::zeroed());
+///     mpc::init2(&mut s, 53);
+///     mpc::init2(&mut c, 53);
+///     mpc::init2(&mut z, 53);
+///     mpc::set_ui(&mut z, 0, mpc::RNDNN);
+///     // sin 0 = 0 and cos 0 = 1 are both exact
+///     let inex = mpc::sin_cos(&mut s, &mut c, &z, mpc::RNDNN, mpc::RNDNN);
+///     let pair = InexactPair::from_ternary(inex);
+///     assert!(pair.first.is_exact() && pair.second.is_exact());
+///     mpc::clear(&mut z);
+///     mpc::clear(&mut c);
+///     mpc::clear(&mut s);
+/// }
+/// ```
+///
+/// [`Inexact`]: struct.Inexact.html
+/// [`fma`]: fn.fma.html
+/// [`sin_cos`]: fn.sin_cos.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InexactPair {
+    /// The ternary value of the first result.
+    pub first: Inexact,
+    /// The ternary value of the second result.
+    pub second: Inexact,
+}
+
+impl InexactPair {
+    /// Decodes the ternary value returned by a function.
+    ///
+    /// See: [Return Value](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#return_002dvalue)
+    #[inline]
+    pub fn from_ternary(inex: c_int) -> InexactPair {
+        InexactPair {
+            first: Inexact::from_ternary(INEX1(inex)),
+            second: Inexact::from_ternary(INEX2(inex)),
+        }
+    }
+
+    /// Encodes the ternary value as returned by a function.
+    ///
+    /// See: [Return Value](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#return_002dvalue)
+    #[inline]
+    pub fn to_ternary(self) -> c_int {
+        self.first.to_ternary() | (self.second.to_ternary() << 4)
+    }
+}
+
 /// See: [`mpc_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#index-mpc_005frnd_005ft)
 pub type rnd_t = c_int;
 
-const RNDN: c_int = mpfr::rnd_t::RNDN as c_int;
-const RNDZ: c_int = mpfr::rnd_t::RNDZ as c_int;
-const RNDU: c_int = mpfr::rnd_t::RNDU as c_int;
-const RNDD: c_int = mpfr::rnd_t::RNDD as c_int;
+const RNDN: c_int = mpfr::rnd_t::RNDN.0;
+const RNDZ: c_int = mpfr::rnd_t::RNDZ.0;
+const RNDU: c_int = mpfr::rnd_t::RNDU.0;
+const RNDD: c_int = mpfr::rnd_t::RNDD.0;
 
 /// See: [Rounding Modes](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#Rounding-Modes)
 pub const RNDNN: c_int = RNDN + (RNDN << 4);
@@ -122,6 +252,39 @@ pub const RNDDZ: c_int = RNDD + (RNDZ << 4);
 pub const RNDDU: c_int = RNDD + (RNDU << 4);
 /// See: [Rounding Modes](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#Rounding-Modes)
 pub const RNDDD: c_int = RNDD + (RNDD << 4);
+/// Returns the rounding mode that rounds the real part using `re` and
+/// the imaginary part using `im`.
+///
+/// Unlike the `RNDxy` constants, this can also be used with
+/// [`mpfr::rnd_t::RNDA`].
+///
+/// See: [Rounding Modes](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#Rounding-Modes)
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::{mpc, mpfr::rnd_t};
+/// assert_eq!(mpc::rnd(rnd_t::RNDZ, rnd_t::RNDU), mpc::RNDZU);
+/// let away = mpc::rnd(rnd_t::RNDA, rnd_t::RNDN);
+/// assert_eq!(mpc::rnd_parts(away), (rnd_t::RNDA, rnd_t::RNDN));
+/// ```
+///
+/// [`mpfr::rnd_t::RNDA`]: ../mpfr/struct.rnd_t.html#associatedconstant.RNDA
+#[inline]
+pub fn rnd(re: mpfr::rnd_t, im: mpfr::rnd_t) -> rnd_t {
+    re.0 + (im.0 << 4)
+}
+
+/// Returns the rounding modes of the real and imaginary parts; this
+/// is the inverse of [`rnd`].
+///
+/// See: [Rounding Modes](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#Rounding-Modes)
+///
+/// [`rnd`]: fn.rnd.html
+#[inline]
+pub fn rnd_parts(rnd: rnd_t) -> (mpfr::rnd_t, mpfr::rnd_t) {
+    (mpfr::rnd_t(rnd & 0x0F), mpfr::rnd_t(rnd >> 4))
+}
 
 /// See: [`mpc_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/GNU-MPC-Basics.html#index-mpc_005ft)
 ///
@@ -133,6 +296,10 @@ pub const RNDDD: c_int = RNDD + (RNDD << 4);
 /// makes use of these internals should list the dependency as
 /// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
 ///
+/// # Thread safety
+///
+/// See the [crate documentation](../index.html#thread-safety).
+///
 /// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -143,6 +310,50 @@ pub struct mpc_t {
     pub im: mpfr::mpfr_t,
 }
 
+/// See: [`mpcr_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005ft)
+///
+/// The functions using this type require MPC 1.3 or later.
+///
+/// # Future compatibility
+///
+/// The fields listed here are considered internal details. These
+/// internals may change in new minor releases of this crate, though
+/// they will be kept unchanged for patch releases. Any code that
+/// makes use of these internals should list the dependency as
+/// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
+///
+/// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct mpcr_t {
+    /// Internal implementation detail: mantissa.
+    pub mant: i64,
+    /// Internal implementation detail: exponent.
+    pub exp: i64,
+}
+
+/// See: [`mpcb_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005ft)
+///
+/// The functions using this type require MPC 1.3 or later.
+///
+/// # Future compatibility
+///
+/// The fields listed here are considered internal details. These
+/// internals may change in new minor releases of this crate, though
+/// they will be kept unchanged for patch releases. Any code that
+/// makes use of these internals should list the dependency as
+/// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
+///
+/// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct mpcb_t {
+    /// Internal implementation detail: center.
+    pub c: mpc_t,
+    /// Internal implementation detail: radius.
+    pub r: mpcr_t,
+}
+
 // Types for function declarations in this file.
 
 type mpz_srcptr = *const gmp::mpz_t;
@@ -153,6 +364,14 @@ type mpfr_srcptr = *const mpfr::mpfr_t;
 type mpfr_ptr = *mut mpfr::mpfr_t;
 type mpc_ptr = *mut mpc_t;
 type mpc_srcptr = *const mpc_t;
+#[cfg(mpc_1_3)]
+type mpcr_ptr = *mut mpcr_t;
+#[cfg(mpc_1_3)]
+type mpcr_srcptr = *const mpcr_t;
+#[cfg(mpc_1_3)]
+type mpcb_ptr = *mut mpcb_t;
+#[cfg(mpc_1_3)]
+type mpcb_srcptr = *const mpcb_t;
 
 extern "C" {
     // Initialization Functions
@@ -163,6 +382,38 @@ extern "C" {
     /// See: [`mpc_init3`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005finit3)
     #[link_name = "mpc_init3"]
     pub fn init3(z: mpc_ptr, prec_r: mpfr::prec_t, prec_i: mpfr::prec_t);
+}
+/// Initializes an [`mpc_t`] with precisions `prec_r` and `prec_i` for
+/// the real and imaginary parts using [`init3`] and returns it. Both
+/// parts are NaN.
+///
+/// The returned value has to be freed using [`clear`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpc;
+/// let mut c = mpc::new3(53, 24);
+/// unsafe {
+///     let (mut pr, mut pi) = (0, 0);
+///     mpc::get_prec2(&mut pr, &mut pi, &c);
+///     assert_eq!((pr, pi), (53, 24));
+///     mpc::clear(&mut c);
+/// }
+/// ```
+///
+/// [`clear`]: fn.clear.html
+/// [`init3`]: fn.init3.html
+/// [`mpc_t`]: struct.mpc_t.html
+#[inline]
+pub fn new3(prec_r: mpfr::prec_t, prec_i: mpfr::prec_t) -> mpc_t {
+    let mut c = MaybeUninit::uninit();
+    unsafe {
+        init3(c.as_mut_ptr(), prec_r, prec_i);
+        c.assume_init()
+    }
+}
+extern "C" {
     /// See: [`mpc_clear`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005fclear)
     #[link_name = "mpc_clear"]
     pub fn clear(z: mpc_ptr);
@@ -262,6 +513,120 @@ extern "C" {
     /// See: [`mpc_free_str`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005ffree_005fstr)
     #[link_name = "mpc_free_str"]
     pub fn free_str(rop: *mut c_char);
+}
+/// Converts `op` to a [`String`] of the form `(re im)` in base `b`.
+///
+/// The string is allocated by MPC, copied, and then freed using
+/// [`free_str`].
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpc_t`].
+///
+/// # Panics
+///
+/// Panics if `b` is not in the range 2 to 36.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpc;
+/// use std::mem;
+/// unsafe {
+///     let mut c = mem::zeroed();
+///     mpc::init2(&mut c, 53);
+///     mpc::set_si_si(&mut c, 3, -4, mpc::RNDNN);
+///     let s = mpc::get_string(10, 2, &c, mpc::RNDNN);
+///     assert_eq!(s, "(3.0 -4.0)");
+///     mpc::clear(&mut c);
+/// }
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`free_str`]: fn.free_str.html
+/// [`mpc_t`]: struct.mpc_t.html
+pub unsafe fn get_string(b: c_int, n: usize, op: mpc_srcptr, rnd: rnd_t) -> String {
+    assert!((2..=36).contains(&b), "base out of range");
+    let s = get_str(b, n, op, rnd);
+    let string = misc::string_from_ptr(s);
+    free_str(s);
+    string
+}
+
+/// Formats an [`mpc_t`] in decimal in the form `(re im)`, where both
+/// parts are formatted using [`mpfr::DisplayMpfr`].
+///
+/// The precision option applies to both parts, while the width, fill
+/// and alignment options apply to the whole. [`Debug`] formats both
+/// parts using the [`Debug`] implementation of
+/// [`mpfr::DisplayMpfr`], which includes the precision, kind and
+/// sign.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpc::{self, DisplayMpc};
+/// let mut c = mpc::new3(53, 53);
+/// unsafe {
+///     mpc::set_d_d(&mut c, 1.5, -0.25, mpc::RNDNN);
+///     let d = DisplayMpc::new(&c);
+///     assert_eq!(format!("{}", d), "(1.5e0 -2.5e-1)");
+///     assert_eq!(format!("{:.2}", d), "(1.50e0 -2.50e-1)");
+///     mpc::clear(&mut c);
+/// }
+/// ```
+///
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`mpc_t`]: struct.mpc_t.html
+/// [`mpfr::DisplayMpfr`]: ../mpfr/struct.DisplayMpfr.html
+#[derive(Clone, Copy)]
+pub struct DisplayMpc<'a> {
+    c: &'a mpc_t,
+}
+
+impl<'a> DisplayMpc<'a> {
+    /// Creates an adapter that formats `c`.
+    ///
+    /// # Safety
+    ///
+    /// `c` must be initialized.
+    #[inline]
+    pub unsafe fn new(c: &'a mpc_t) -> DisplayMpc<'a> {
+        DisplayMpc { c }
+    }
+
+    #[inline]
+    fn parts(&self) -> (mpfr::DisplayMpfr<'a>, mpfr::DisplayMpfr<'a>) {
+        unsafe {
+            (
+                mpfr::DisplayMpfr::new(&self.c.re),
+                mpfr::DisplayMpfr::new(&self.c.im),
+            )
+        }
+    }
+}
+
+impl<'a> Display for DisplayMpc<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let (re, im) = self.parts();
+        let s = match f.precision() {
+            Some(p) => format!("({:.*} {:.*})", p, re, p, im),
+            None => format!("({} {})", re, im),
+        };
+        misc::pad_str(f, &s)
+    }
+}
+
+impl<'a> Debug for DisplayMpc<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let (re, im) = self.parts();
+        f.debug_struct("mpc_t")
+            .field("re", &re)
+            .field("im", &im)
+            .finish()
+    }
+}
+extern "C" {
     /// See: [`mpc_inp_str`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005finp_005fstr)
     #[link_name = "mpc_inp_str"]
     pub fn inp_str(
@@ -442,6 +807,20 @@ extern "C" {
     #[link_name = "mpc_div_2si"]
     pub fn div_2si(rop: mpc_ptr, op1: mpc_srcptr, op2: c_long, rnd: rnd_t) -> c_int;
 
+    /// See: [`mpc_sum`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005fsum)
+    ///
+    /// This function requires MPC 1.2 or later.
+    #[cfg(mpc_1_2)]
+    #[link_name = "mpc_sum"]
+    pub fn sum(rop: mpc_ptr, tab: *const mpc_ptr, n: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpc_dot`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005fdot)
+    ///
+    /// This function requires MPC 1.2 or later.
+    #[cfg(mpc_1_2)]
+    #[link_name = "mpc_dot"]
+    pub fn dot(rop: mpc_ptr, a: *const mpc_ptr, b: *const mpc_ptr, n: c_ulong, rnd: rnd_t)
+        -> c_int;
+
     // Power Functions and Logarithms
 
     /// See: [`mpc_sqrt`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005fsqrt)
@@ -534,6 +913,274 @@ extern "C" {
     /// See: [`mpc_get_version`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005fget_005fversion)
     #[link_name = "mpc_get_version"]
     pub fn get_version() -> *const c_char;
+    /// See: [`mpc_agm`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005fagm)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpc_agm"]
+    pub fn agm(rop: mpc_ptr, a: mpc_srcptr, b: mpc_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpc_eta_fund`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-mpc_005feta_005ffund)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpc_eta_fund"]
+    pub fn eta_fund(rop: mpc_ptr, op: mpc_srcptr, rnd: rnd_t) -> c_int;
+}
+
+extern "C" {
+    // Ball Arithmetic
+
+    /// See: [`mpcr_inf_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005finf_005fp)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_inf_p"]
+    pub fn mpcr_inf_p(r: mpcr_srcptr) -> c_int;
+    /// See: [`mpcr_zero_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fzero_005fp)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_zero_p"]
+    pub fn mpcr_zero_p(r: mpcr_srcptr) -> c_int;
+    /// See: [`mpcr_lt_half_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005flt_005fhalf_005fp)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_lt_half_p"]
+    pub fn mpcr_lt_half_p(r: mpcr_srcptr) -> c_int;
+    /// See: [`mpcr_cmp`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fcmp)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_cmp"]
+    pub fn mpcr_cmp(r: mpcr_srcptr, s: mpcr_srcptr) -> c_int;
+    /// See: [`mpcr_set_inf`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fset_005finf)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_set_inf"]
+    pub fn mpcr_set_inf(r: mpcr_ptr);
+    /// See: [`mpcr_set_zero`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fset_005fzero)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_set_zero"]
+    pub fn mpcr_set_zero(r: mpcr_ptr);
+    /// See: [`mpcr_set_one`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fset_005fone)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_set_one"]
+    pub fn mpcr_set_one(r: mpcr_ptr);
+    /// See: [`mpcr_set`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fset)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_set"]
+    pub fn mpcr_set(r: mpcr_ptr, s: mpcr_srcptr);
+    /// See: [`mpcr_set_ui64_2si64`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fset_005fui64_005f2si64)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_set_ui64_2si64"]
+    pub fn mpcr_set_ui64_2si64(r: mpcr_ptr, mant: u64, exp: i64);
+    /// See: [`mpcr_max`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fmax)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_max"]
+    pub fn mpcr_max(r: mpcr_ptr, s: mpcr_srcptr, t: mpcr_srcptr);
+    /// See: [`mpcr_get_exp`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fget_005fexp)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_get_exp"]
+    pub fn mpcr_get_exp(r: mpcr_srcptr) -> i64;
+    /// See: [`mpcr_out_str`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fout_005fstr)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_out_str"]
+    pub fn mpcr_out_str(f: *mut FILE, r: mpcr_srcptr);
+    /// See: [`mpcr_mul`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fmul)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_mul"]
+    pub fn mpcr_mul(r: mpcr_ptr, s: mpcr_srcptr, t: mpcr_srcptr);
+    /// See: [`mpcr_mul_2ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fmul_005f2ui)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_mul_2ui"]
+    pub fn mpcr_mul_2ui(r: mpcr_ptr, s: mpcr_srcptr, e: c_ulong);
+    /// See: [`mpcr_sqr`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fsqr)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_sqr"]
+    pub fn mpcr_sqr(r: mpcr_ptr, s: mpcr_srcptr);
+    /// See: [`mpcr_add`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fadd)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_add"]
+    pub fn mpcr_add(r: mpcr_ptr, s: mpcr_srcptr, t: mpcr_srcptr);
+    /// See: [`mpcr_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fsub)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_sub"]
+    pub fn mpcr_sub(r: mpcr_ptr, s: mpcr_srcptr, t: mpcr_srcptr);
+    /// See: [`mpcr_sub_rnd`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fsub_005frnd)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_sub_rnd"]
+    pub fn mpcr_sub_rnd(r: mpcr_ptr, s: mpcr_srcptr, t: mpcr_srcptr, rnd: mpfr::rnd_t);
+    /// See: [`mpcr_div`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fdiv)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_div"]
+    pub fn mpcr_div(r: mpcr_ptr, s: mpcr_srcptr, t: mpcr_srcptr);
+    /// See: [`mpcr_div_2ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fdiv_005f2ui)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_div_2ui"]
+    pub fn mpcr_div_2ui(r: mpcr_ptr, s: mpcr_srcptr, e: c_ulong);
+    /// See: [`mpcr_sqrt`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fsqrt)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_sqrt"]
+    pub fn mpcr_sqrt(r: mpcr_ptr, s: mpcr_srcptr);
+    /// See: [`mpcr_add_rounding_error`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fadd_005frounding_005ferror)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_add_rounding_error"]
+    pub fn mpcr_add_rounding_error(r: mpcr_ptr, p: mpfr::prec_t, rnd: mpfr::rnd_t);
+    /// See: [`mpcr_c_abs_rnd`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcr_005fc_005fabs_005frnd)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcr_c_abs_rnd"]
+    pub fn mpcr_c_abs_rnd(r: mpcr_ptr, z: mpc_srcptr, rnd: mpfr::rnd_t);
+    /// See: [`mpcb_init`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005finit)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_init"]
+    pub fn mpcb_init(z: mpcb_ptr);
+    /// See: [`mpcb_clear`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fclear)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_clear"]
+    pub fn mpcb_clear(z: mpcb_ptr);
+    /// See: [`mpcb_get_prec`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fget_005fprec)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_get_prec"]
+    pub fn mpcb_get_prec(z: mpcb_srcptr) -> mpfr::prec_t;
+    /// See: [`mpcb_set`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fset)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_set"]
+    pub fn mpcb_set(z: mpcb_ptr, z1: mpcb_srcptr);
+    /// See: [`mpcb_set_inf`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fset_005finf)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_set_inf"]
+    pub fn mpcb_set_inf(z: mpcb_ptr);
+    /// See: [`mpcb_set_c`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fset_005fc)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_set_c"]
+    pub fn mpcb_set_c(
+        z: mpcb_ptr,
+        c: mpc_srcptr,
+        prec: mpfr::prec_t,
+        err_re: c_ulong,
+        err_im: c_ulong,
+    );
+    /// See: [`mpcb_set_ui_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fset_005fui_005fui)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_set_ui_ui"]
+    pub fn mpcb_set_ui_ui(z: mpcb_ptr, re: c_ulong, im: c_ulong, prec: mpfr::prec_t);
+    /// See: [`mpcb_neg`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fneg)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_neg"]
+    pub fn mpcb_neg(z: mpcb_ptr, z1: mpcb_srcptr);
+    /// See: [`mpcb_add`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fadd)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_add"]
+    pub fn mpcb_add(z: mpcb_ptr, z1: mpcb_srcptr, z2: mpcb_srcptr);
+    /// See: [`mpcb_mul`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fmul)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_mul"]
+    pub fn mpcb_mul(z: mpcb_ptr, z1: mpcb_srcptr, z2: mpcb_srcptr);
+    /// See: [`mpcb_sqr`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fsqr)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_sqr"]
+    pub fn mpcb_sqr(z: mpcb_ptr, z1: mpcb_srcptr);
+    /// See: [`mpcb_pow_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fpow_005fui)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_pow_ui"]
+    pub fn mpcb_pow_ui(z: mpcb_ptr, z1: mpcb_srcptr, e: c_ulong);
+    /// See: [`mpcb_sqrt`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fsqrt)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_sqrt"]
+    pub fn mpcb_sqrt(z: mpcb_ptr, z1: mpcb_srcptr);
+    /// See: [`mpcb_div`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fdiv)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_div"]
+    pub fn mpcb_div(z: mpcb_ptr, z1: mpcb_srcptr, z2: mpcb_srcptr);
+    /// See: [`mpcb_div_2ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fdiv_005f2ui)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_div_2ui"]
+    pub fn mpcb_div_2ui(z: mpcb_ptr, z1: mpcb_srcptr, e: c_ulong);
+    /// See: [`mpcb_can_round`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fcan_005fround)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_can_round"]
+    pub fn mpcb_can_round(
+        z: mpcb_srcptr,
+        prec_re: mpfr::prec_t,
+        prec_im: mpfr::prec_t,
+        rnd: rnd_t,
+    ) -> c_int;
+    /// See: [`mpcb_round`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Ball-Arithmetic.html#index-mpcb_005fround)
+    ///
+    /// This function requires MPC 1.3 or later.
+    #[cfg(mpc_1_3)]
+    #[link_name = "mpcb_round"]
+    pub fn mpcb_round(c: mpc_ptr, z: mpcb_srcptr, rnd: rnd_t) -> c_int;
 }
 /// See: [`MPC_VERSION`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpc/Complex-Functions.html#index-MPC_005fVERSION)
 pub const VERSION: c_int = (VERSION_MAJOR << 16) | (VERSION_MINOR << 8) | VERSION_PATCHLEVEL;
@@ -556,6 +1203,7 @@ mod tests {
     use gmp;
     use mpc;
     use mpfr;
+    use std::mem;
 
     #[test]
     fn check_real_imag_offsets() {
@@ -582,6 +1230,148 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_ball_offsets() {
+        let limb: gmp::limb_t = 1 << (gmp::LIMB_BITS - 1);
+        let mut limbs = [limb, limb];
+        let part = mpfr::mpfr_t {
+            prec: 1,
+            sign: 1,
+            exp: 0,
+            d: &mut limbs[0],
+        };
+        let b = mpc::mpcb_t {
+            c: mpc::mpc_t { re: part, im: part },
+            r: mpc::mpcr_t { mant: 0, exp: 0 },
+        };
+        let base = &b as *const mpc::mpcb_t as usize;
+        let r = &b.r as *const mpc::mpcr_t as usize;
+        assert_eq!(&b.c as *const mpc::mpc_t as usize, base);
+        assert_eq!(r - base, mem::size_of::<mpc::mpc_t>());
+        assert_eq!(&b.r.mant as *const i64 as usize, r);
+        assert_eq!(&b.r.exp as *const i64 as usize, r + 8);
+        assert_eq!(mem::size_of::<mpc::mpcr_t>(), 16);
+    }
+
+    #[cfg(mpc_1_3)]
+    #[test]
+    fn check_ball_arithmetic() {
+        unsafe {
+            let mut b = mem::zeroed();
+            mpc::mpcb_init(&mut b);
+            mpc::mpcb_set_ui_ui(&mut b, 3, 4, 64);
+            assert_eq!(mpc::mpcr_zero_p(&b.r), 1);
+            let bp: *mut mpc::mpcb_t = &mut b;
+            mpc::mpcb_sqrt(bp, bp);
+            mpc::mpcb_pow_ui(bp, bp, 2);
+            assert_eq!(mpc::mpcr_inf_p(&b.r), 0);
+            assert_ne!(mpc::mpcb_can_round(&b, 16, 16, mpc::RNDNN), 0);
+            let mut c = mem::zeroed();
+            mpc::init2(&mut c, 16);
+            mpc::mpcb_round(&mut c, &b, mpc::RNDNN);
+            assert_eq!(mpc::cmp_si_si(&c, 3, 4), 0);
+            mpc::clear(&mut c);
+            mpc::mpcb_clear(&mut b);
+        }
+    }
+
+    #[test]
+    fn check_rnd_parts() {
+        assert_eq!(
+            mpc::rnd_parts(mpc::RNDNZ),
+            (mpfr::rnd_t::RNDN, mpfr::rnd_t::RNDZ)
+        );
+        assert_eq!(
+            mpc::rnd_parts(mpc::RNDDU),
+            (mpfr::rnd_t::RNDD, mpfr::rnd_t::RNDU)
+        );
+    }
+
+    #[test]
+    fn check_rnd_and_inexact() {
+        use mpc::{Inexact, InexactPair};
+        use mpfr::rnd_t;
+        use std::cmp::Ordering;
+
+        let modes = [
+            rnd_t::RNDN,
+            rnd_t::RNDZ,
+            rnd_t::RNDU,
+            rnd_t::RNDD,
+            rnd_t::RNDA,
+        ];
+        for &re in modes.iter() {
+            for &im in modes.iter() {
+                assert_eq!(mpc::rnd_parts(mpc::rnd(re, im)), (re, im));
+            }
+        }
+        assert_eq!(mpc::rnd(rnd_t::RNDD, rnd_t::RNDZ), mpc::RNDDZ);
+
+        let ords = [Ordering::Less, Ordering::Equal, Ordering::Greater];
+        for &re in ords.iter() {
+            for &im in ords.iter() {
+                let inex = Inexact { re, im };
+                assert_eq!(Inexact::from_ternary(inex.to_ternary()), inex);
+                let pair = InexactPair {
+                    first: inex,
+                    second: Inexact { re: im, im: re },
+                };
+                assert_eq!(InexactPair::from_ternary(pair.to_ternary()), pair);
+            }
+        }
+
+        unsafe {
+            let (mut a, mut b, mut z) = (mem::zeroed(), mem::zeroed(), mem::zeroed());
+            mpc::init2(&mut a, 2);
+            mpc::init2(&mut b, 2);
+            mpc::init2(&mut z, 53);
+            // 5 becomes 6 when rounding away from zero, -5 becomes -4
+            // when rounding to nearest with ties to even
+            mpc::set_si_si(&mut z, 5, -5, mpc::RNDNN);
+            let inex = mpc::set(&mut a, &z, mpc::rnd(rnd_t::RNDA, rnd_t::RNDN));
+            let inex = Inexact::from_ternary(inex);
+            assert_eq!(inex.re, Ordering::Greater);
+            assert_eq!(inex.im, Ordering::Greater);
+            assert_eq!(mpc::cmp_si_si(&a, 6, -4), 0);
+            let inex = mpc::sin_cos(&mut a, &mut b, &z, mpc::RNDNN, mpc::RNDNN);
+            let pair = InexactPair::from_ternary(inex);
+            assert!(!pair.first.is_exact() && !pair.second.is_exact());
+            mpc::clear(&mut z);
+            mpc::clear(&mut b);
+            mpc::clear(&mut a);
+        }
+    }
+
+    #[test]
+    fn check_get_string() {
+        unsafe {
+            let mut c = mem::zeroed();
+            mpc::init2(&mut c, 8);
+            mpc::set_ui(&mut c, 255, mpc::RNDNN);
+            assert_eq!(mpc::get_string(16, 0, &c, mpc::RNDNN), "(f.f0p+4 +0)");
+            mpc::clear(&mut c);
+        }
+    }
+
+    #[test]
+    fn check_display() {
+        unsafe {
+            let mut c = mpc::new3(53, 24);
+            let d = mpc::DisplayMpc::new(&c);
+            assert_eq!(format!("{:>12}|", d), "   (NaN NaN)|");
+            mpc::set_d_d(&mut c, -0.0, 2.0, mpc::RNDNN);
+            let d = mpc::DisplayMpc::new(&c);
+            assert_eq!(format!("{:.1}", d), "(-0.0e0 2.0e0)");
+            assert_eq!(
+                format!("{:?}", d),
+                "mpc_t { \
+                 re: mpfr_t { prec: 53, kind: Zero, sign: -1, value: -0e0 }, \
+                 im: mpfr_t { prec: 24, kind: Regular, sign: 1, value: 2e0 } }"
+            );
+            mpc::clear(&mut c);
+        }
+    }
+
     #[cfg(not(newer_cache))]
     #[test]
     fn check_version() {
@@ -600,4 +1390,32 @@ mod tests {
         let from_const_string = unsafe { CStr::from_ptr(mpc::VERSION_STRING) };
         assert_eq!(from_const_string.to_str().unwrap(), version);
     }
+
+    #[test]
+    fn check_threads() {
+        use std::thread;
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<mpc::mpc_t>();
+        unsafe {
+            let mut c = thread::spawn(|| {
+                let mut c = mpc::new3(32, 32);
+                mpc::set_si_si(&mut c, 1, 2, mpc::RNDNN);
+                c
+            })
+            .join()
+            .unwrap();
+            let cp: *mut mpc::mpc_t = &mut c;
+            mpc::sqr(cp, cp, mpc::RNDNN);
+            thread::scope(|scope| {
+                let cr = &c;
+                for _ in 0..4 {
+                    scope.spawn(move || {
+                        assert_eq!(mpc::get_prec(cr), 32);
+                        assert_eq!(mpc::cmp_si_si(cr, -3, 4), 0);
+                    });
+                }
+            });
+            thread::spawn(move || mpc::clear(&mut c)).join().unwrap();
+        }
+    }
 }
@@ -20,21 +20,16 @@ Function and type bindings for the [MPFR] library.
 # Examples
 
 ```rust
-# #[cfg(all(maybe_uninit, not(nightly_maybe_uninit)))] {
 use gmp_mpfr_sys::mpfr;
-use std::mem::MaybeUninit;
 let one_third = 1.0_f64 / 3.0;
+let mut f = mpfr::new2(53);
 unsafe {
-    let mut f = MaybeUninit::uninit();
-    mpfr::init2(f.as_mut_ptr(), 53);
-    let mut f = f.assume_init();
     let dir = mpfr::set_d(&mut f, one_third, mpfr::rnd_t::RNDN);
     assert_eq!(dir, 0);
     let d = mpfr::get_d(&f, mpfr::rnd_t::RNDN);
     assert_eq!(d, one_third);
     mpfr::clear(&mut f);
 }
-# }
 ```
 
 The following example is a translation of the [MPFR sample] found on
@@ -45,39 +40,17 @@ using 200-bit precision. The program writes:
 `Sum is 2.7182818284590452353602874713526624977572470936999595749669131`
 
 ```rust
-# #[cfg(all(maybe_uninit, not(nightly_maybe_uninit)))]
-# macro_rules! cond {
-#     ($($t:tt)*) => {
-#         $($t)*
-#     };
-# }
-# #[cfg(any(not(maybe_uninit), nightly_maybe_uninit))]
-# macro_rules! cond {
-#     ($($t:tt)*) => {
-#         fn main() {}
-#     };
-# }
-# cond! {
 use gmp_mpfr_sys::mpfr::{self, mpfr_t, rnd_t};
-use std::mem::MaybeUninit;
 use std::os::raw::c_int;
 
 fn main() {
+    let mut t = mpfr::new2(200);
+    let mut s = mpfr::new2(200);
+    let mut u = mpfr::new2(200);
     unsafe {
-        let mut t = MaybeUninit::uninit();
-        mpfr::init2(t.as_mut_ptr(), 200);
-        let mut t = t.assume_init();
         mpfr::set_d(&mut t, 1.0, rnd_t::RNDD);
-
-        let mut s = MaybeUninit::uninit();
-        mpfr::init2(s.as_mut_ptr(), 200);
-        let mut s = s.assume_init();
         mpfr::set_d(&mut s, 1.0, rnd_t::RNDD);
 
-        let mut u = MaybeUninit::uninit();
-        mpfr::init2(u.as_mut_ptr(), 200);
-        let mut u = u.assume_init();
-
         for i in 1..=100 {
             mpfr::mul_ui(&mut t, &t, i, rnd_t::RNDU);
             mpfr::set_d(&mut u, 1.0, rnd_t::RNDD);
@@ -104,9 +77,8 @@ unsafe fn mpfr_to_string(
     use std::fmt::Write;
     use std::ptr;
 
-    let mut exp = MaybeUninit::uninit();
-    let str = mpfr::get_str(ptr::null_mut(), exp.as_mut_ptr(), base, n, op, rnd);
-    let exp = exp.assume_init();
+    let mut exp = 0;
+    let str = mpfr::get_str(ptr::null_mut(), &mut exp, base, n, op, rnd);
     let mut buf = CStr::from_ptr(str).to_string_lossy().into_owned();
     mpfr::free_str(str);
     if mpfr::regular_p(op) != 0 {
@@ -119,7 +91,6 @@ unsafe fn mpfr_to_string(
     }
     buf
 }
-# }
 ```
 
 [MPFR sample]: https://www.mpfr.org/sample.html
@@ -130,38 +101,111 @@ unsafe fn mpfr_to_string(
 use gmp;
 use libc::{intmax_t, uintmax_t, FILE};
 use misc;
-use std::mem;
+use std::cell::UnsafeCell;
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
 use std::os::raw::{c_char, c_int, c_long, c_uint, c_ulong, c_void};
+use std::ptr;
 
 /// See: [`mpfr_prec_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fprec_005ft)
 pub type prec_t = c_long;
 
-// TODO: breaking change: do not use enum
 /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-#[repr(C)]
-#[derive(Clone, Copy, Debug)]
-#[allow(deprecated)]
-pub enum rnd_t {
+///
+/// This is a transparent wrapper around a C `int` rather than a Rust
+/// `enum`, so that any value received from C is valid, even one which
+/// does not correspond to one of the rounding modes listed here. The
+/// rounding modes are associated constants, for example
+/// [`rnd_t::RNDN`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::rnd_t;
+/// use std::os::raw::c_int;
+/// assert_eq!(rnd_t::RNDZ.0, 1);
+/// assert_eq!(rnd_t::from(3), rnd_t::RNDD);
+/// assert_eq!(c_int::from(rnd_t::RNDA), 4);
+/// assert_eq!(format!("{:?}", rnd_t::RNDU), "RNDU");
+/// assert_eq!(format!("{:?}", rnd_t(42)), "rnd_t(42)");
+/// ```
+///
+/// [`rnd_t::RNDN`]: #associatedconstant.RNDN
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct rnd_t(pub c_int);
+
+impl rnd_t {
     /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-    RNDN = 0,
+    pub const RNDN: rnd_t = rnd_t(0);
     /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-    RNDZ = 1,
+    pub const RNDZ: rnd_t = rnd_t(1);
     /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-    RNDU = 2,
+    pub const RNDU: rnd_t = rnd_t(2);
     /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-    RNDD = 3,
+    pub const RNDD: rnd_t = rnd_t(3);
     /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-    RNDA = 4,
+    pub const RNDA: rnd_t = rnd_t(4);
     /// See: [`mpfr_rnd_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005frnd_005ft)
-    RNDF = 5,
+    pub const RNDF: rnd_t = rnd_t(5);
     #[doc(hidden)]
     #[deprecated(since = "1.1.0", note = "do not use!")]
-    RNDNA = -1,
+    pub const RNDNA: rnd_t = rnd_t(-1);
+}
+
+impl Debug for rnd_t {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let name = match *self {
+            rnd_t::RNDN => "RNDN",
+            rnd_t::RNDZ => "RNDZ",
+            rnd_t::RNDU => "RNDU",
+            rnd_t::RNDD => "RNDD",
+            rnd_t::RNDA => "RNDA",
+            rnd_t::RNDF => "RNDF",
+            _ => return write!(f, "rnd_t({})", self.0),
+        };
+        f.write_str(name)
+    }
+}
+
+impl From<c_int> for rnd_t {
+    #[inline]
+    fn from(src: c_int) -> rnd_t {
+        rnd_t(src)
+    }
+}
+
+impl From<rnd_t> for c_int {
+    #[inline]
+    fn from(src: rnd_t) -> c_int {
+        src.0
+    }
 }
 
 /// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
 pub type flags_t = c_uint;
 
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_UNDERFLOW: flags_t = 1;
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_OVERFLOW: flags_t = 2;
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_NAN: flags_t = 4;
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_INEXACT: flags_t = 8;
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_ERANGE: flags_t = 16;
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_DIVBY0: flags_t = 32;
+/// See: [`mpfr_flags_t`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Basics.html#index-mpfr_005fflags_005ft)
+pub const FLAGS_ALL: flags_t = 63;
+
 /// See: [Exception Related Functions](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#Exception-Related-Functions)
 pub type exp_t = c_long;
 
@@ -183,6 +227,10 @@ pub const PREC_MAX: prec_t = ((!(0 as uprec_t) >> 1) - 256) as prec_t;
 /// makes use of these internals should list the dependency as
 /// `version = "~1.1"` inside [*Cargo.toml*], *not* `version = "1.1"`.
 ///
+/// # Thread safety
+///
+/// See the [crate documentation](../index.html#thread-safety).
+///
 /// [*Cargo.toml*]: https://doc.rust-lang.org/cargo/guide/dependencies.html
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -197,6 +245,31 @@ pub struct mpfr_t {
     pub d: *mut gmp::limb_t,
 }
 
+// The significand that `d` points to is allocated for this number
+// alone, and MPFR keeps its caches and exponent range in thread-local
+// storage rather than in the number, so the number can move between
+// threads; MPFR only reads its `mpfr_srcptr` operands, so concurrent
+// reads are fine.
+unsafe impl Send for mpfr_t {}
+unsafe impl Sync for mpfr_t {}
+
+/// An x87 extended precision number, which is the layout of C `long
+/// double` on x86_64 GNU/Linux.
+///
+/// The first ten bytes hold the number in little-endian order: a
+/// 64-bit significand with an explicit integer bit, followed by the
+/// 15-bit biased exponent and the sign bit. The last six bytes are
+/// padding and are ignored.
+///
+/// This type is only available on x86_64 GNU/Linux.
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[repr(C, align(16))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct long_double {
+    /// The bytes of the number in memory.
+    pub bytes: [u8; 16],
+}
+
 /// See: [`mpfr_custom_init_set`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcustom_005finit_005fset)
 pub const NAN_KIND: c_int = 0;
 /// See: [`mpfr_custom_init_set`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcustom_005finit_005fset)
@@ -231,12 +304,72 @@ const EXP_NAN: exp_t = 1 - EXP_MAX;
 const EXP_ZERO: exp_t = 0 - EXP_MAX;
 const EXP_INF: exp_t = 2 - EXP_MAX;
 
+// Wrappers compiled by build.rs. A long double argument is passed in
+// memory and a long double return value is left on the x87 stack,
+// neither of which can be expressed in Rust, so the wrappers pass
+// long double values by pointer instead.
+
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+extern "C" {
+    fn gmp_mpfr_sys_mpfr_set_ld(rop: mpfr_ptr, op: *const long_double, rnd: rnd_t) -> c_int;
+    fn gmp_mpfr_sys_mpfr_get_ld(rop: *mut long_double, op: mpfr_srcptr, rnd: rnd_t);
+    fn gmp_mpfr_sys_mpfr_get_ld_2exp(
+        rop: *mut long_double,
+        exp: *mut c_long,
+        op: mpfr_srcptr,
+        rnd: rnd_t,
+    );
+    fn gmp_mpfr_sys_mpfr_cmp_ld(op1: mpfr_srcptr, op2: *const long_double) -> c_int;
+}
+
+#[cfg(feature = "float128")]
+extern "C" {
+    fn gmp_mpfr_sys_mpfr_set_float128(rop: mpfr_ptr, op: *const c_void, rnd: rnd_t) -> c_int;
+    fn gmp_mpfr_sys_mpfr_get_float128(rop: *mut c_void, op: mpfr_srcptr, rnd: rnd_t);
+}
+
+#[cfg(feature = "decimal-float")]
+extern "C" {
+    fn gmp_mpfr_sys_mpfr_set_decimal64(rop: mpfr_ptr, op: *const c_void, rnd: rnd_t) -> c_int;
+    fn gmp_mpfr_sys_mpfr_get_decimal64(rop: *mut c_void, op: mpfr_srcptr, rnd: rnd_t);
+}
+
 // Initialization Functions
 
 extern "C" {
     /// See: [`mpfr_init2`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005finit2)
     #[link_name = "mpfr_init2"]
     pub fn init2(x: mpfr_ptr, prec: prec_t);
+}
+/// Initializes an [`mpfr_t`] with precision `prec` using [`init2`]
+/// and returns it. The value is NaN.
+///
+/// The returned value has to be freed using [`clear`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr;
+/// let mut f = mpfr::new2(53);
+/// unsafe {
+///     assert_eq!(mpfr::get_prec(&f), 53);
+///     assert_ne!(mpfr::nan_p(&f), 0);
+///     mpfr::clear(&mut f);
+/// }
+/// ```
+///
+/// [`clear`]: fn.clear.html
+/// [`init2`]: fn.init2.html
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[inline]
+pub fn new2(prec: prec_t) -> mpfr_t {
+    let mut f = MaybeUninit::uninit();
+    unsafe {
+        init2(f.as_mut_ptr(), prec);
+        f.assume_init()
+    }
+}
+extern "C" {
     /// See: [`mpfr_inits2`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005finits2)
     #[link_name = "mpfr_inits2"]
     pub fn inits2(prec: prec_t, x: mpfr_ptr, ...);
@@ -292,12 +425,195 @@ extern "C" {
     /// See: [`mpfr_set_sj`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005fsj)
     #[link_name = "__gmpfr_set_sj"]
     pub fn set_sj(rop: mpfr_ptr, op: intmax_t, rnd: rnd_t) -> c_int;
+}
+/// Sets the value of `rop` from a `u128`, rounded in the direction
+/// `rnd`.
+///
+/// Like [`set_uj`], the return value is the ternary value.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+/// [`set_uj`]: fn.set_uj.html
+#[inline]
+pub unsafe fn set_u128(rop: mpfr_ptr, op: u128, rnd: rnd_t) -> c_int {
+    set_abs_128(rop, op, false, rnd)
+}
+/// Sets the value of `rop` from an `i128`, rounded in the direction
+/// `rnd`.
+///
+/// Like [`set_sj`], the return value is the ternary value.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+/// [`set_sj`]: fn.set_sj.html
+#[inline]
+pub unsafe fn set_i128(rop: mpfr_ptr, op: i128, rnd: rnd_t) -> c_int {
+    set_abs_128(rop, op.unsigned_abs(), op < 0, rnd)
+}
+
+// MPFR does not support nails, so every limb holds LIMB_BITS bits.
+const LIMBS_128: usize = 128 / gmp::LIMB_BITS as usize;
+
+unsafe fn set_abs_128(rop: mpfr_ptr, abs: u128, neg: bool, rnd: rnd_t) -> c_int {
+    let mut limbs: [gmp::limb_t; LIMBS_128] = [0; LIMBS_128];
+    let mut size = 0;
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        *limb = (abs >> (i * gmp::LIMB_BITS as usize)) as gmp::limb_t;
+        if *limb != 0 {
+            size = i + 1;
+        }
+    }
+    let mut z = gmp::MPZ_ROINIT_N(limbs.as_mut_ptr(), size as gmp::size_t);
+    if neg {
+        z.size = -z.size;
+    }
+    set_z(rop, &z, rnd)
+}
+/// Sets the value of `rop` from the bit pattern of an IEEE 754
+/// binary16 number, rounded in the direction `rnd`.
+///
+/// Subnormals and signed zeros are supported. Any NaN, whatever its
+/// payload, is converted to a NaN. The return value is the ternary
+/// value, which can only be non-zero if the precision of `rop` is
+/// less than 11 bits.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, rnd_t};
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     mpfr::init2(&mut f, 11);
+///     // smallest positive subnormal, 2^−24
+///     assert_eq!(mpfr::set_f16_bits(&mut f, 0x0001, rnd_t::RNDN), 0);
+///     assert_eq!(mpfr::cmp_ui_2exp(&f, 1, -24), 0);
+///     mpfr::clear(&mut f);
+/// }
+/// ```
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[inline]
+pub unsafe fn set_f16_bits(rop: mpfr_ptr, bits: u16, rnd: rnd_t) -> c_int {
+    set_ieee_bits(rop, u32::from(bits), 5, 10, rnd)
+}
+/// Sets the value of `rop` from the bit pattern of a bfloat16
+/// number, rounded in the direction `rnd`.
+///
+/// Subnormals and signed zeros are supported. Any NaN, whatever its
+/// payload, is converted to a NaN. The return value is the ternary
+/// value, which can only be non-zero if the precision of `rop` is
+/// less than 8 bits.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[inline]
+pub unsafe fn set_bf16_bits(rop: mpfr_ptr, bits: u16, rnd: rnd_t) -> c_int {
+    set_ieee_bits(rop, u32::from(bits), 8, 7, rnd)
+}
+
+unsafe fn set_ieee_bits(
+    rop: mpfr_ptr,
+    bits: u32,
+    exp_bits: u32,
+    man_bits: u32,
+    rnd: rnd_t,
+) -> c_int {
+    let neg = bits >> (exp_bits + man_bits) != 0;
+    let exp_field = (bits >> man_bits) & ((1 << exp_bits) - 1);
+    let man_field = bits & ((1 << man_bits) - 1);
+    let bias = (1 << (exp_bits - 1)) - 1;
+    let (man, exp) = if exp_field == (1 << exp_bits) - 1 {
+        if man_field == 0 {
+            set_inf(rop, if neg { -1 } else { 1 });
+        } else {
+            set_nan(rop);
+        }
+        return 0;
+    } else if exp_field == 0 {
+        if man_field == 0 {
+            set_zero(rop, if neg { -1 } else { 1 });
+            return 0;
+        }
+        (man_field, 1 - bias)
+    } else {
+        (man_field | (1 << man_bits), exp_field as exp_t - bias)
+    };
+    let man = if neg { -(man as c_long) } else { man as c_long };
+    set_si_2exp(rop, man, exp - man_bits as exp_t, rnd)
+}
+extern "C" {
     /// See: [`mpfr_set_flt`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005fflt)
     #[link_name = "mpfr_set_flt"]
     pub fn set_flt(rop: mpfr_ptr, op: f32, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_set_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005fd)
     #[link_name = "mpfr_set_d"]
     pub fn set_d(rop: mpfr_ptr, op: f64, rnd: rnd_t) -> c_int;
+}
+/// See: [`mpfr_set_ld`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005fld)
+///
+/// This function is only available on x86_64 GNU/Linux.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+pub unsafe fn set_ld(rop: mpfr_ptr, op: long_double, rnd: rnd_t) -> c_int {
+    gmp_mpfr_sys_mpfr_set_ld(rop, &op, rnd)
+}
+/// See: [`mpfr_set_float128`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005ffloat128)
+///
+/// Since Rust has no `__float128` type, `op` is the bit pattern of an
+/// IEEE 754 binary128 number.
+///
+/// This function is only available with the `float128` feature.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(feature = "float128")]
+#[inline]
+pub unsafe fn set_float128(rop: mpfr_ptr, op: u128, rnd: rnd_t) -> c_int {
+    gmp_mpfr_sys_mpfr_set_float128(rop, &op as *const u128 as *const c_void, rnd)
+}
+/// See: [`mpfr_set_decimal64`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005fdecimal64)
+///
+/// Since Rust has no `_Decimal64` type, `op` is the bit pattern of an
+/// IEEE 754 decimal64 number in the encoding used by the C compiler,
+/// which is the binary integer decimal (BID) encoding on x86 and
+/// x86_64.
+///
+/// This function is only available with the `decimal-float` feature.
+///
+/// # Safety
+///
+/// `rop` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(feature = "decimal-float")]
+#[inline]
+pub unsafe fn set_decimal64(rop: mpfr_ptr, op: u64, rnd: rnd_t) -> c_int {
+    gmp_mpfr_sys_mpfr_set_decimal64(rop, &op as *const u64 as *const c_void, rnd)
+}
+extern "C" {
     /// See: [`mpfr_set_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fset_005fz)
     #[link_name = "mpfr_set_z"]
     pub fn set_z(rop: mpfr_ptr, op: mpz_srcptr, rnd: rnd_t) -> c_int;
@@ -374,6 +690,21 @@ pub unsafe extern "C" fn init_set_d(rop: mpfr_ptr, op: f64, rnd: rnd_t) -> c_int
     init(rop);
     set_d(rop, op, rnd)
 }
+/// See: [`mpfr_init_set_ld`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005finit_005fset_005fld)
+///
+/// This function is only available on x86_64 GNU/Linux.
+///
+/// # Safety
+///
+/// `rop` must point to memory that can hold an [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+pub unsafe fn init_set_ld(rop: mpfr_ptr, op: long_double, rnd: rnd_t) -> c_int {
+    init(rop);
+    set_ld(rop, op, rnd)
+}
 /// See: [`mpfr_init_set_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005finit_005fset_005fz)
 #[inline]
 pub unsafe extern "C" fn init_set_z(rop: mpfr_ptr, op: mpz_srcptr, rnd: rnd_t) -> c_int {
@@ -405,6 +736,97 @@ extern "C" {
     /// See: [`mpfr_get_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fd)
     #[link_name = "mpfr_get_d"]
     pub fn get_d(op: mpfr_srcptr, rnd: rnd_t) -> f64;
+}
+/// See: [`mpfr_get_ld`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fld)
+///
+/// This function is only available on x86_64 GNU/Linux.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+pub unsafe fn get_ld(op: mpfr_srcptr, rnd: rnd_t) -> long_double {
+    let mut rop = long_double::default();
+    gmp_mpfr_sys_mpfr_get_ld(&mut rop, op, rnd);
+    rop
+}
+/// See: [`mpfr_get_float128`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005ffloat128)
+///
+/// Since Rust has no `__float128` type, the bit pattern of an IEEE
+/// 754 binary128 number is returned.
+///
+/// This function is only available with the `float128` feature.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(feature = "float128")] {
+/// use gmp_mpfr_sys::mpfr::{self, rnd_t};
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     mpfr::init2(&mut f, 113);
+///     mpfr::set_si(&mut f, -2, rnd_t::RNDN);
+///     assert_eq!(mpfr::get_float128(&f, rnd_t::RNDN), 0xC000 << 112);
+///     mpfr::clear(&mut f);
+/// }
+/// # }
+/// ```
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(feature = "float128")]
+#[inline]
+pub unsafe fn get_float128(op: mpfr_srcptr, rnd: rnd_t) -> u128 {
+    let mut rop = 0u128;
+    gmp_mpfr_sys_mpfr_get_float128(&mut rop as *mut u128 as *mut c_void, op, rnd);
+    rop
+}
+/// See: [`mpfr_get_decimal64`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fdecimal64)
+///
+/// Since Rust has no `_Decimal64` type, the bit pattern of an IEEE
+/// 754 decimal64 number is returned, in the encoding used by the C
+/// compiler, which is the binary integer decimal (BID) encoding on
+/// x86 and x86_64.
+///
+/// This function is only available with the `decimal-float` feature.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(all(feature = "decimal-float", target_arch = "x86_64"))] {
+/// use gmp_mpfr_sys::mpfr::{self, rnd_t};
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     mpfr::init2(&mut f, 64);
+///     mpfr::set_si(&mut f, -7, rnd_t::RNDN);
+///     // sign 1, biased exponent 383 for 10^−15, coefficient 7 × 10^15
+///     assert_eq!(mpfr::get_decimal64(&f, rnd_t::RNDN), 0xAFF8_DE76_816D_8000);
+///     mpfr::clear(&mut f);
+/// }
+/// # }
+/// ```
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(feature = "decimal-float")]
+#[inline]
+pub unsafe fn get_decimal64(op: mpfr_srcptr, rnd: rnd_t) -> u64 {
+    let mut rop = 0u64;
+    gmp_mpfr_sys_mpfr_get_decimal64(&mut rop as *mut u64 as *mut c_void, op, rnd);
+    rop
+}
+extern "C" {
     /// See: [`mpfr_get_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fsi)
     #[link_name = "mpfr_get_si"]
     pub fn get_si(op: mpfr_srcptr, rnd: rnd_t) -> c_long;
@@ -420,6 +842,25 @@ extern "C" {
     /// See: [`mpfr_get_d_2exp`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fd_005f2exp)
     #[link_name = "mpfr_get_d_2exp"]
     pub fn get_d_2exp(exp: *mut c_long, op: mpfr_srcptr, rnd: rnd_t) -> f64;
+}
+/// See: [`mpfr_get_ld_2exp`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fld_005f2exp)
+///
+/// This function is only available on x86_64 GNU/Linux.
+///
+/// # Safety
+///
+/// `exp` must be valid for writes, and `op` must point to an
+/// initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+pub unsafe fn get_ld_2exp(exp: *mut c_long, op: mpfr_srcptr, rnd: rnd_t) -> long_double {
+    let mut rop = long_double::default();
+    gmp_mpfr_sys_mpfr_get_ld_2exp(&mut rop, exp, op, rnd);
+    rop
+}
+extern "C" {
     /// See: [`mpfr_frexp`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffrexp)
     #[link_name = "mpfr_frexp"]
     pub fn frexp(exp: *mut exp_t, y: mpfr_ptr, x: mpfr_srcptr, rnd: rnd_t) -> c_int;
@@ -445,9 +886,166 @@ extern "C" {
         op: mpfr_srcptr,
         rnd: rnd_t,
     ) -> *mut c_char;
+    /// See: [`mpfr_get_str_ndigits`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fget_005fstr_005fndigits)
+    ///
+    /// This function requires MPFR 4.1 or later.
+    #[cfg(mpfr_4_1)]
+    #[link_name = "mpfr_get_str_ndigits"]
+    pub fn get_str_ndigits(b: c_int, p: prec_t) -> usize;
     /// See: [`mpfr_free_str`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffree_005fstr)
     #[link_name = "mpfr_free_str"]
     pub fn free_str(str: *mut c_char);
+}
+/// Converts `op` to a [`String`] of significant digits in base `b`
+/// and returns it together with the exponent.
+///
+/// As with [`get_str`], there is an implicit radix point to the left
+/// of the first digit. If `n` is zero, the number of digits is chosen
+/// as for [`get_str`].
+///
+/// If `n` is not zero, the buffer is allocated by Rust. Otherwise the
+/// string is allocated by MPFR, copied, and then freed using
+/// [`free_str`].
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// # Panics
+///
+/// Panics if `b` is not in the range 2 to 62 or −36 to −2.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, rnd_t};
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     mpfr::init2(&mut f, 53);
+///     mpfr::set_d(&mut f, -12.75, rnd_t::RNDN);
+///     let s = mpfr::get_string(10, 5, &f, rnd_t::RNDN);
+///     assert_eq!(s, (String::from("-12750"), 2));
+///     let s = mpfr::get_string(10, 2, &f, rnd_t::RNDZ);
+///     assert_eq!(s, (String::from("-12"), 2));
+///     mpfr::clear(&mut f);
+/// }
+/// ```
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+/// [`free_str`]: fn.free_str.html
+/// [`get_str`]: fn.get_str.html
+/// [`mpfr_t`]: struct.mpfr_t.html
+pub unsafe fn get_string(b: c_int, n: usize, op: mpfr_srcptr, rnd: rnd_t) -> (String, exp_t) {
+    misc::check_base(b);
+    let mut exp = 0;
+    if n != 0 {
+        // room for the sign, the nul terminator and "@Inf@"
+        let mut buf = Vec::<u8>::with_capacity(cmp::max(n + 2, 7));
+        get_str(buf.as_mut_ptr() as *mut c_char, &mut exp, b, n, op, rnd);
+        (misc::string_from_buf(buf), exp)
+    } else {
+        let s = get_str(ptr::null_mut(), &mut exp, b, 0, op, rnd);
+        let string = misc::string_from_ptr(s);
+        free_str(s);
+        (string, exp)
+    }
+}
+
+/// Formats an [`mpfr_t`] in decimal using [`get_str`], in the same
+/// style as the [`LowerExp`] implementation of [`f64`].
+///
+/// NaN is formatted as `NaN`, and infinities as `inf` and `-inf`.
+/// The precision option sets the number of digits after the decimal
+/// point, rounding to the nearest; without it, enough digits are used
+/// to read the number back exactly, and trailing zeros are removed.
+/// The width, fill, alignment, sign and zero-padding options are also
+/// supported.
+///
+/// [`Debug`] also includes the precision in bits, the kind as returned
+/// by [`custom_get_kind`], and the sign. Formatting leaves the
+/// exception flags unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, rnd_t, DisplayMpfr};
+/// let mut f = mpfr::new2(53);
+/// unsafe {
+///     mpfr::set_d(&mut f, -12.75, rnd_t::RNDN);
+///     let d = DisplayMpfr::new(&f);
+///     assert_eq!(format!("{}", d), "-1.275e1");
+///     assert_eq!(format!("{:.1}", d), "-1.3e1");
+///     assert_eq!(
+///         format!("{:?}", d),
+///         "mpfr_t { prec: 53, kind: Regular, sign: -1, value: -1.275e1 }"
+///     );
+///     mpfr::set_zero(&mut f, -1);
+///     assert_eq!(format!("{}", DisplayMpfr::new(&f)), "-0e0");
+///     mpfr::set_inf(&mut f, 1);
+///     assert_eq!(format!("{:+}", DisplayMpfr::new(&f)), "+inf");
+///     mpfr::clear(&mut f);
+/// }
+/// ```
+///
+/// [`Debug`]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
+/// [`LowerExp`]: https://doc.rust-lang.org/std/fmt/trait.LowerExp.html
+/// [`custom_get_kind`]: fn.custom_get_kind.html
+/// [`f64`]: https://doc.rust-lang.org/std/primitive.f64.html
+/// [`get_str`]: fn.get_str.html
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[derive(Clone, Copy)]
+pub struct DisplayMpfr<'a> {
+    f: &'a mpfr_t,
+}
+
+impl<'a> DisplayMpfr<'a> {
+    /// Creates an adapter that formats `f`.
+    ///
+    /// # Safety
+    ///
+    /// `f` must be initialized.
+    #[inline]
+    pub unsafe fn new(f: &'a mpfr_t) -> DisplayMpfr<'a> {
+        DisplayMpfr { f }
+    }
+}
+
+impl<'a> Display for DisplayMpfr<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let kind = unsafe { custom_get_kind(self.f) };
+        match kind.abs() {
+            NAN_KIND => misc::pad_str(f, "NaN"),
+            INF_KIND => f.pad_integral(kind > 0, "", "inf"),
+            ZERO_KIND => misc::fmt_sci(f, if kind > 0 { "" } else { "-" }, 0),
+            _ => {
+                let n = f.precision().map_or(0, |p| p + 1);
+                // formatting should not leave the inexact flag set
+                let _guard = FlagsGuard::new();
+                let (s, exp) = unsafe { get_string(10, n, self.f, rnd_t::RNDN) };
+                misc::fmt_sci(f, &s, exp)
+            }
+        }
+    }
+}
+
+impl<'a> Debug for DisplayMpfr<'a> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let kind = match unsafe { custom_get_kind(self.f) }.abs() {
+            NAN_KIND => "NaN",
+            INF_KIND => "Inf",
+            ZERO_KIND => "Zero",
+            _ => "Regular",
+        };
+        f.debug_struct("mpfr_t")
+            .field("prec", &unsafe { get_prec(self.f) })
+            .field("kind", &format_args!("{}", kind))
+            .field("sign", &self.f.sign)
+            .field("value", &format_args!("{}", self))
+            .finish()
+    }
+}
+extern "C" {
     /// See: [`mpfr_fits_ulong_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffits_005fulong_005fp)
     #[link_name = "mpfr_fits_ulong_p"]
     pub fn fits_ulong_p(op: mpfr_srcptr, rnd: rnd_t) -> c_int;
@@ -472,52 +1070,455 @@ extern "C" {
     /// See: [`mpfr_fits_intmax_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffits_005fintmax_005fp)
     #[link_name = "mpfr_fits_intmax_p"]
     pub fn fits_intmax_p(op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+}
+/// Converts `op` to a `u128` after rounding it to an integer in the
+/// direction `rnd`.
+///
+/// Like [`get_uj`], if `op` is NaN, 0 is returned, and if the rounded
+/// value does not fit, 0 or [`u128::MAX`] is returned depending on
+/// the sign of `op`; in both cases the erange flag is set. If the
+/// rounded value fits but is not equal to `op`, the inexact flag is
+/// set. Use [`fits_u128_p`] to check beforehand.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// [`fits_u128_p`]: fn.fits_u128_p.html
+/// [`get_uj`]: fn.get_uj.html
+/// [`mpfr_t`]: struct.mpfr_t.html
+/// [`u128::MAX`]: https://doc.rust-lang.org/std/primitive.u128.html#associatedconstant.MAX
+pub unsafe fn get_u128(op: mpfr_srcptr, rnd: rnd_t) -> u128 {
+    if fits_u128_p(op, rnd) == 0 {
+        set_erangeflag();
+        return if nan_p(op) != 0 || signbit(op) != 0 {
+            0
+        } else {
+            u128::MAX
+        };
+    }
+    if zero_p(op) != 0 {
+        return 0;
+    }
+    with_rint_128(op, rnd, |x| abs_128(x))
+}
+/// Converts `op` to an `i128` after rounding it to an integer in the
+/// direction `rnd`.
+///
+/// Like [`get_sj`], if `op` is NaN, 0 is returned, and if the
+/// rounded value does not fit, [`i128::MIN`] or [`i128::MAX`] is
+/// returned depending on the sign of `op`; in both cases the erange
+/// flag is set. If the rounded value fits but is not equal to `op`,
+/// the inexact flag is set. Use [`fits_i128_p`] to check beforehand.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// [`fits_i128_p`]: fn.fits_i128_p.html
+/// [`get_sj`]: fn.get_sj.html
+/// [`i128::MAX`]: https://doc.rust-lang.org/std/primitive.i128.html#associatedconstant.MAX
+/// [`i128::MIN`]: https://doc.rust-lang.org/std/primitive.i128.html#associatedconstant.MIN
+/// [`mpfr_t`]: struct.mpfr_t.html
+pub unsafe fn get_i128(op: mpfr_srcptr, rnd: rnd_t) -> i128 {
+    if fits_i128_p(op, rnd) == 0 {
+        set_erangeflag();
+        return if nan_p(op) != 0 {
+            0
+        } else if signbit(op) != 0 {
+            i128::MIN
+        } else {
+            i128::MAX
+        };
+    }
+    if zero_p(op) != 0 {
+        return 0;
+    }
+    with_rint_128(op, rnd, |x| {
+        let abs = abs_128(x) as i128;
+        // −2^127 is its own wrapping negation
+        if signbit(x) != 0 {
+            abs.wrapping_neg()
+        } else {
+            abs
+        }
+    })
+}
 
-    // Basic Arithmetic Functions
+// Rounds op to an integer with 128 bits of precision inside the
+// widest exponent range, so that the only flag that can be raised is
+// inexact, and passes the result to f.
+unsafe fn with_rint_128<R, F>(op: mpfr_srcptr, rnd: rnd_t, f: F) -> R
+where
+    F: FnOnce(&mpfr_t) -> R,
+{
+    let _range = ExpRangeGuard::new(get_emin_min(), get_emax_max())
+        .expect("widest exponent range not supported");
+    let mut x = mem::zeroed();
+    init2(&mut x, 128);
+    rint(&mut x, op, rnd);
+    let ret = f(&x);
+    clear(&mut x);
+    ret
+}
 
-    /// See: [`mpfr_add`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd)
-    #[link_name = "mpfr_add"]
-    pub fn add(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_add_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fui)
-    #[link_name = "mpfr_add_ui"]
-    pub fn add_ui(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_ulong, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_add_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fsi)
-    #[link_name = "mpfr_add_si"]
-    pub fn add_si(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_long, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_add_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fd)
-    #[link_name = "mpfr_add_d"]
-    pub fn add_d(rop: mpfr_ptr, op1: mpfr_srcptr, op2: f64, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_add_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fz)
-    #[link_name = "mpfr_add_z"]
-    pub fn add_z(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpz_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_add_q`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fq)
-    #[link_name = "mpfr_add_q"]
-    pub fn add_q(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpq_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub)
-    #[link_name = "mpfr_sub"]
-    pub fn sub(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_ui_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fui_005fsub)
-    #[link_name = "mpfr_ui_sub"]
-    pub fn ui_sub(rop: mpfr_ptr, op1: c_ulong, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_sub_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fui)
-    #[link_name = "mpfr_sub_ui"]
-    pub fn sub_ui(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_ulong, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_si_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsi_005fsub)
-    #[link_name = "mpfr_si_sub"]
-    pub fn si_sub(rop: mpfr_ptr, op1: c_long, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_sub_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fsi)
-    #[link_name = "mpfr_sub_si"]
-    pub fn sub_si(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_long, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_d_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fd_005fsub)
-    #[link_name = "mpfr_d_sub"]
-    pub fn d_sub(rop: mpfr_ptr, op1: f64, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_sub_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fd)
-    #[link_name = "mpfr_sub_d"]
-    pub fn sub_d(rop: mpfr_ptr, op1: mpfr_srcptr, op2: f64, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_z_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fz_005fsub)
-    #[link_name = "mpfr_z_sub"]
-    pub fn z_sub(rop: mpfr_ptr, op1: mpz_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
-    /// See: [`mpfr_sub_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fz)
+// Returns the absolute value of x, which must be an integer with a
+// precision of 128 bits such that 0 < |x| < 2^128.
+unsafe fn abs_128(x: &mpfr_t) -> u128 {
+    if zero_p(x) != 0 {
+        return 0;
+    }
+    let mut sh = x.exp as i32;
+    let mut ret = 0u128;
+    for n in (0..LIMBS_128).rev() {
+        sh -= gmp::LIMB_BITS;
+        let limb = u128::from(*x.d.add(n));
+        ret |= if sh >= 0 { limb << sh } else { limb >> -sh };
+    }
+    ret
+}
+/// Returns non-zero if `op` would fit in a `u128` when rounded to an
+/// integer in the direction `rnd`.
+///
+/// No flags are modified.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+pub unsafe fn fits_u128_p(op: mpfr_srcptr, rnd: rnd_t) -> c_int {
+    if nan_p(op) != 0 || inf_p(op) != 0 {
+        return 0;
+    }
+    if zero_p(op) != 0 {
+        return 1;
+    }
+    let _flags = FlagsGuard::new();
+    let fits = with_rint_128(op, rnd, |x| {
+        zero_p(x) != 0 || (signbit(x) == 0 && x.exp <= 128)
+    });
+    fits as c_int
+}
+/// Returns non-zero if `op` would fit in an `i128` when rounded to
+/// an integer in the direction `rnd`.
+///
+/// No flags are modified.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+pub unsafe fn fits_i128_p(op: mpfr_srcptr, rnd: rnd_t) -> c_int {
+    if nan_p(op) != 0 || inf_p(op) != 0 {
+        return 0;
+    }
+    if zero_p(op) != 0 {
+        return 1;
+    }
+    let _flags = FlagsGuard::new();
+    let fits = with_rint_128(op, rnd, |x| {
+        zero_p(x) != 0 || x.exp <= 127 || cmp_si_2exp(x, -1, 127) == 0
+    });
+    fits as c_int
+}
+
+// Converts op using get_ui if it fits in a c_ulong, and using
+// get_u128 otherwise. As op is an integer, no flags are modified.
+unsafe fn try_get_unsigned<T>(op: mpfr_srcptr) -> Result<T, gmp::ConversionError>
+where
+    T: TryFrom<c_ulong> + TryFrom<u128>,
+{
+    if nan_p(op) != 0 {
+        Err(gmp::ConversionError::Nan)
+    } else if inf_p(op) != 0 {
+        Err(gmp::ConversionError::Overflow)
+    } else if integer_p(op) == 0 {
+        Err(gmp::ConversionError::NotInteger)
+    } else if fits_ulong_p(op, rnd_t::RNDZ) != 0 {
+        T::try_from(get_ui(op, rnd_t::RNDZ)).map_err(|_| gmp::ConversionError::Overflow)
+    } else if fits_u128_p(op, rnd_t::RNDZ) != 0 {
+        T::try_from(get_u128(op, rnd_t::RNDZ)).map_err(|_| gmp::ConversionError::Overflow)
+    } else {
+        Err(gmp::ConversionError::Overflow)
+    }
+}
+
+// Converts op using get_si if it fits in a c_long, and using get_i128
+// otherwise. As op is an integer, no flags are modified.
+unsafe fn try_get_signed<T>(op: mpfr_srcptr) -> Result<T, gmp::ConversionError>
+where
+    T: TryFrom<c_long> + TryFrom<i128>,
+{
+    if nan_p(op) != 0 {
+        Err(gmp::ConversionError::Nan)
+    } else if inf_p(op) != 0 {
+        Err(gmp::ConversionError::Overflow)
+    } else if integer_p(op) == 0 {
+        Err(gmp::ConversionError::NotInteger)
+    } else if fits_slong_p(op, rnd_t::RNDZ) != 0 {
+        T::try_from(get_si(op, rnd_t::RNDZ)).map_err(|_| gmp::ConversionError::Overflow)
+    } else if fits_i128_p(op, rnd_t::RNDZ) != 0 {
+        T::try_from(get_i128(op, rnd_t::RNDZ)).map_err(|_| gmp::ConversionError::Overflow)
+    } else {
+        Err(gmp::ConversionError::Overflow)
+    }
+}
+
+// Converts op if it is an integer, NaN or infinite. Otherwise, op is
+// rounded to 128 bits in the direction rnd, and Inexact is returned if
+// the result fits.
+unsafe fn try_get_round<T, G>(
+    op: mpfr_srcptr,
+    rnd: rnd_t,
+    get: G,
+) -> Result<T, gmp::ConversionError>
+where
+    G: FnOnce(mpfr_srcptr) -> Result<T, gmp::ConversionError>,
+{
+    if nan_p(op) != 0 || inf_p(op) != 0 || integer_p(op) != 0 {
+        return get(op);
+    }
+    let _flags = FlagsGuard::new();
+    match with_rint_128(op, rnd, |x| get(x)) {
+        Ok(_) => Err(gmp::ConversionError::Inexact),
+        Err(e) => Err(e),
+    }
+}
+
+macro_rules! try_get {
+    ($($name:ident, $round:ident($T:ident, $helper:ident);)*) => { $(
+        #[doc = concat!("Converts `op` to [`", stringify!($T), "`] if it is an integer.")]
+        ///
+        /// Unlike the `get` functions, this does not round, and no
+        /// flags are modified.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConversionError::Nan`] if `op` is NaN,
+        /// [`ConversionError::NotInteger`] if `op` has a fractional
+        /// part, and [`ConversionError::Overflow`] if `op` is infinite
+        /// or does not fit.
+        ///
+        /// # Safety
+        ///
+        /// `op` must point to an initialized [`mpfr_t`].
+        ///
+        /// [`ConversionError::Nan`]: ../gmp/enum.ConversionError.html#variant.Nan
+        /// [`ConversionError::NotInteger`]: ../gmp/enum.ConversionError.html#variant.NotInteger
+        /// [`ConversionError::Overflow`]: ../gmp/enum.ConversionError.html#variant.Overflow
+        #[doc = concat!(
+            "[`", stringify!($T), "`]: https://doc.rust-lang.org/std/primitive.",
+            stringify!($T), ".html"
+        )]
+        /// [`mpfr_t`]: struct.mpfr_t.html
+        #[inline]
+        pub unsafe fn $name(op: mpfr_srcptr) -> Result<$T, gmp::ConversionError> {
+            $helper(op)
+        }
+
+        #[doc = concat!(
+            "Converts `op` to [`", stringify!($T), "`] if it is an integer, and otherwise ",
+            "checks whether it fits in [`", stringify!($T), "`] when rounded to an integer ",
+            "in the direction `rnd`."
+        )]
+        ///
+        /// No flags are modified.
+        ///
+        /// # Errors
+        ///
+        /// Returns [`ConversionError::Nan`] if `op` is NaN,
+        /// [`ConversionError::Overflow`] if `op` is infinite or the
+        /// rounded value does not fit, and
+        /// [`ConversionError::Inexact`] if the rounded value fits but
+        /// is not equal to `op`.
+        ///
+        /// # Safety
+        ///
+        /// `op` must point to an initialized [`mpfr_t`].
+        ///
+        /// [`ConversionError::Inexact`]: ../gmp/enum.ConversionError.html#variant.Inexact
+        /// [`ConversionError::Nan`]: ../gmp/enum.ConversionError.html#variant.Nan
+        /// [`ConversionError::Overflow`]: ../gmp/enum.ConversionError.html#variant.Overflow
+        #[doc = concat!(
+            "[`", stringify!($T), "`]: https://doc.rust-lang.org/std/primitive.",
+            stringify!($T), ".html"
+        )]
+        /// [`mpfr_t`]: struct.mpfr_t.html
+        #[inline]
+        pub unsafe fn $round(op: mpfr_srcptr, rnd: rnd_t) -> Result<$T, gmp::ConversionError> {
+            try_get_round(op, rnd, |op| $helper(op))
+        }
+    )* };
+}
+try_get! {
+    try_get_i8, try_get_i8_round(i8, try_get_signed);
+    try_get_u8, try_get_u8_round(u8, try_get_unsigned);
+    try_get_i16, try_get_i16_round(i16, try_get_signed);
+    try_get_u16, try_get_u16_round(u16, try_get_unsigned);
+    try_get_i32, try_get_i32_round(i32, try_get_signed);
+    try_get_u32, try_get_u32_round(u32, try_get_unsigned);
+    try_get_i64, try_get_i64_round(i64, try_get_signed);
+    try_get_u64, try_get_u64_round(u64, try_get_unsigned);
+    try_get_i128, try_get_i128_round(i128, try_get_signed);
+    try_get_u128, try_get_u128_round(u128, try_get_unsigned);
+    try_get_isize, try_get_isize_round(isize, try_get_signed);
+    try_get_usize, try_get_usize_round(usize, try_get_unsigned);
+}
+/// Converts `op` to the bit pattern of an IEEE 754 binary16 number,
+/// correctly rounded in the direction `rnd`.
+///
+/// Subnormals and signed zeros are produced as required, and values
+/// that overflow become infinite or the largest finite value
+/// depending on `rnd`, as in IEEE 754. A NaN is always converted to
+/// the canonical quiet NaN 0x7E00. No flags are modified.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, rnd_t};
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     mpfr::init2(&mut f, 53);
+///     mpfr::set_d(&mut f, 65520.0, rnd_t::RNDN);
+///     // 65520 is halfway between the largest finite value and 2^16
+///     assert_eq!(mpfr::get_f16_bits(&f, rnd_t::RNDN), 0x7C00);
+///     assert_eq!(mpfr::get_f16_bits(&f, rnd_t::RNDZ), 0x7BFF);
+///     mpfr::clear(&mut f);
+/// }
+/// ```
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[inline]
+pub unsafe fn get_f16_bits(op: mpfr_srcptr, rnd: rnd_t) -> u16 {
+    get_ieee_bits(op, 5, 10, rnd) as u16
+}
+/// Converts `op` to the bit pattern of a bfloat16 number, correctly
+/// rounded in the direction `rnd`.
+///
+/// Subnormals and signed zeros are produced as required, and values
+/// that overflow become infinite or the largest finite value
+/// depending on `rnd`, as in IEEE 754. A NaN is always converted to
+/// the canonical quiet NaN 0x7FC0. No flags are modified.
+///
+/// # Safety
+///
+/// `op` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[inline]
+pub unsafe fn get_bf16_bits(op: mpfr_srcptr, rnd: rnd_t) -> u16 {
+    get_ieee_bits(op, 8, 7, rnd) as u16
+}
+
+unsafe fn get_ieee_bits(op: mpfr_srcptr, exp_bits: u32, man_bits: u32, rnd: rnd_t) -> u32 {
+    let inf_bits = ((1 << exp_bits) - 1) << man_bits;
+    if nan_p(op) != 0 {
+        return inf_bits | (1 << (man_bits - 1));
+    }
+    let sign_bit = if signbit(op) != 0 {
+        1 << (exp_bits + man_bits)
+    } else {
+        0
+    };
+    if inf_p(op) != 0 {
+        return sign_bit | inf_bits;
+    }
+    if zero_p(op) != 0 {
+        return sign_bit;
+    }
+    let bias = (1 << (exp_bits - 1)) - 1;
+    let away = match rnd {
+        rnd_t::RNDN | rnd_t::RNDA => true,
+        rnd_t::RNDU => sign_bit == 0,
+        rnd_t::RNDD => sign_bit != 0,
+        _ => false,
+    };
+    let overflow_bits = if away { inf_bits } else { inf_bits - 1 };
+    // op is in [2^(exp - 1), 2^exp)
+    let exp = get_exp(op) - 1;
+    if exp > bias {
+        return sign_bit | overflow_bits;
+    }
+    // Scale op so that the unit in the last place is 1 and round it
+    // to an integer. If rounding carries into the next binade, the
+    // carry propagates into the exponent field, which is also what
+    // happens when a subnormal is rounded up to a normal number.
+    let (base, ulp_exp) = if exp >= 1 - bias {
+        (
+            ((exp + bias - 1) as u32) << man_bits,
+            exp - man_bits as exp_t,
+        )
+    } else {
+        (0, 1 - bias - man_bits as exp_t)
+    };
+    let _flags = FlagsGuard::new();
+    let _range = ExpRangeGuard::new(get_emin_min(), get_emax_max())
+        .expect("widest exponent range not supported");
+    let mut scaled = mem::zeroed();
+    init2(&mut scaled, get_prec(op));
+    mul_2si(&mut scaled, op, -ulp_exp as c_long, rnd_t::RNDN);
+    let mut int = mem::zeroed();
+    init2(&mut int, man_bits as prec_t + 2);
+    rint(&mut int, &scaled, rnd);
+    let ulps = get_si(&int, rnd_t::RNDN).unsigned_abs() as u32;
+    clear(&mut int);
+    clear(&mut scaled);
+    sign_bit | (base + ulps).min(overflow_bits)
+}
+extern "C" {
+
+    // Basic Arithmetic Functions
+
+    /// See: [`mpfr_add`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd)
+    #[link_name = "mpfr_add"]
+    pub fn add(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_add_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fui)
+    #[link_name = "mpfr_add_ui"]
+    pub fn add_ui(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_add_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fsi)
+    #[link_name = "mpfr_add_si"]
+    pub fn add_si(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_long, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_add_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fd)
+    #[link_name = "mpfr_add_d"]
+    pub fn add_d(rop: mpfr_ptr, op1: mpfr_srcptr, op2: f64, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_add_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fz)
+    #[link_name = "mpfr_add_z"]
+    pub fn add_z(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpz_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_add_q`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fadd_005fq)
+    #[link_name = "mpfr_add_q"]
+    pub fn add_q(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpq_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub)
+    #[link_name = "mpfr_sub"]
+    pub fn sub(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_ui_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fui_005fsub)
+    #[link_name = "mpfr_ui_sub"]
+    pub fn ui_sub(rop: mpfr_ptr, op1: c_ulong, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sub_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fui)
+    #[link_name = "mpfr_sub_ui"]
+    pub fn sub_ui(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_si_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsi_005fsub)
+    #[link_name = "mpfr_si_sub"]
+    pub fn si_sub(rop: mpfr_ptr, op1: c_long, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sub_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fsi)
+    #[link_name = "mpfr_sub_si"]
+    pub fn sub_si(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_long, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_d_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fd_005fsub)
+    #[link_name = "mpfr_d_sub"]
+    pub fn d_sub(rop: mpfr_ptr, op1: f64, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sub_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fd)
+    #[link_name = "mpfr_sub_d"]
+    pub fn sub_d(rop: mpfr_ptr, op1: mpfr_srcptr, op2: f64, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_z_sub`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fz_005fsub)
+    #[link_name = "mpfr_z_sub"]
+    pub fn z_sub(rop: mpfr_ptr, op1: mpz_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sub_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fz)
     #[link_name = "mpfr_sub_z"]
     pub fn sub_z(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpz_srcptr, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_sub_q`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsub_005fq)
@@ -586,6 +1587,12 @@ extern "C" {
     /// See: [`mpfr_rootn_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005frootn_005fui)
     #[link_name = "mpfr_root"]
     pub fn rootn_ui(rop: mpfr_ptr, op: mpfr_srcptr, k: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_rootn_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005frootn_005fsi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_rootn_si"]
+    pub fn rootn_si(rop: mpfr_ptr, op: mpfr_srcptr, k: c_long, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_root`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005froot)
     #[link_name = "mpfr_root"]
     #[deprecated(
@@ -596,6 +1603,12 @@ extern "C" {
     /// See: [`mpfr_pow`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpow)
     #[link_name = "mpfr_pow"]
     pub fn pow(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_powr`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpowr)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_powr"]
+    pub fn powr(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpfr_srcptr, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_pow_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpow_005fui)
     #[link_name = "mpfr_pow_ui"]
     pub fn pow_ui(rop: mpfr_ptr, op1: mpfr_srcptr, op2: c_ulong, rnd: rnd_t) -> c_int;
@@ -605,6 +1618,34 @@ extern "C" {
     /// See: [`mpfr_pow_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpow_005fz)
     #[link_name = "mpfr_pow_z"]
     pub fn pow_z(rop: mpfr_ptr, op1: mpfr_srcptr, op2: mpz_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_pow_uj`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpow_005fuj)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "__gmpfr_mpfr_pow_uj"]
+    pub fn pow_uj(rop: mpfr_ptr, op1: mpfr_srcptr, op2: uintmax_t, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_pow_sj`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpow_005fsj)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "__gmpfr_mpfr_pow_sj"]
+    pub fn pow_sj(rop: mpfr_ptr, op1: mpfr_srcptr, op2: intmax_t, rnd: rnd_t) -> c_int;
+}
+/// See: [`mpfr_pown`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fpown)
+///
+/// This function requires MPFR 4.2 or later.
+///
+/// # Safety
+///
+/// `rop` and `op1` must point to initialized [`mpfr_t`] values.
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(mpfr_4_2)]
+#[inline]
+pub unsafe extern "C" fn pown(rop: mpfr_ptr, op1: mpfr_srcptr, op2: intmax_t, rnd: rnd_t) -> c_int {
+    pow_sj(rop, op1, op2, rnd)
+}
+extern "C" {
     /// See: [`mpfr_ui_pow_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fui_005fpow_005fui)
     #[link_name = "mpfr_ui_pow_ui"]
     pub fn ui_pow_ui(rop: mpfr_ptr, op1: c_ulong, op2: c_ulong, rnd: rnd_t) -> c_int;
@@ -663,6 +1704,22 @@ extern "C" {
     /// See: [`mpfr_cmp_d`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcmp_005fd)
     #[link_name = "mpfr_cmp_d"]
     pub fn cmp_d(op1: mpfr_srcptr, op2: f64) -> c_int;
+}
+/// See: [`mpfr_cmp_ld`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcmp_005fld)
+///
+/// This function is only available on x86_64 GNU/Linux.
+///
+/// # Safety
+///
+/// `op1` must point to an initialized [`mpfr_t`].
+///
+/// [`mpfr_t`]: struct.mpfr_t.html
+#[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+#[inline]
+pub unsafe fn cmp_ld(op1: mpfr_srcptr, op2: long_double) -> c_int {
+    gmp_mpfr_sys_mpfr_cmp_ld(op1, &op2)
+}
+extern "C" {
     /// See: [`mpfr_cmp_z`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcmp_005fz)
     #[link_name = "mpfr_cmp_z"]
     pub fn cmp_z(op1: mpfr_srcptr, op2: mpz_srcptr) -> c_int;
@@ -681,6 +1738,12 @@ extern "C" {
     /// See: [`mpfr_cmpabs`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcmpabs)
     #[link_name = "mpfr_cmpabs"]
     pub fn cmpabs(op1: mpfr_srcptr, op2: mpfr_srcptr) -> c_int;
+    /// See: [`mpfr_cmpabs_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcmpabs_005fui)
+    ///
+    /// This function requires MPFR 4.1 or later.
+    #[cfg(mpfr_4_1)]
+    #[link_name = "mpfr_cmpabs_ui"]
+    pub fn cmpabs_ui(op1: mpfr_srcptr, op2: c_ulong) -> c_int;
 }
 /// See: [`mpfr_nan_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fnan_005fp)
 #[inline]
@@ -757,6 +1820,12 @@ extern "C" {
     /// See: [`mpfr_unordered_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005funordered_005fp)
     #[link_name = "mpfr_unordered_p"]
     pub fn unordered_p(op1: mpfr_srcptr, op2: mpfr_srcptr) -> c_int;
+    /// See: [`mpfr_total_order_p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ftotal_005forder_005fp)
+    ///
+    /// This function requires MPFR 4.1 or later.
+    #[cfg(mpfr_4_1)]
+    #[link_name = "mpfr_total_order_p"]
+    pub fn total_order_p(x: mpfr_srcptr, y: mpfr_srcptr) -> c_int;
 
     // Special Functions
 
@@ -775,6 +1844,18 @@ extern "C" {
     /// See: [`mpfr_log1p`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005flog1p)
     #[link_name = "mpfr_log1p"]
     pub fn log1p(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_log2p1`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005flog2p1)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_log2p1"]
+    pub fn log2p1(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_log10p1`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005flog10p1)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_log10p1"]
+    pub fn log10p1(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_exp`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fexp)
     #[link_name = "mpfr_exp"]
     pub fn exp(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
@@ -787,6 +1868,24 @@ extern "C" {
     /// See: [`mpfr_expm1`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fexpm1)
     #[link_name = "mpfr_expm1"]
     pub fn expm1(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_exp2m1`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fexp2m1)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_exp2m1"]
+    pub fn exp2m1(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_exp10m1`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fexp10m1)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_exp10m1"]
+    pub fn exp10m1(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_compound_si`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcompound_005fsi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_compound_si"]
+    pub fn compound_si(rop: mpfr_ptr, op: mpfr_srcptr, n: c_long, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_cos`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcos)
     #[link_name = "mpfr_cos"]
     pub fn cos(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
@@ -796,6 +1895,42 @@ extern "C" {
     /// See: [`mpfr_tan`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ftan)
     #[link_name = "mpfr_tan"]
     pub fn tan(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_cosu`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcosu)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_cosu"]
+    pub fn cosu(rop: mpfr_ptr, op: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sinu`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsinu)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_sinu"]
+    pub fn sinu(rop: mpfr_ptr, op: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_tanu`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ftanu)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_tanu"]
+    pub fn tanu(rop: mpfr_ptr, op: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_cospi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcospi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_cospi"]
+    pub fn cospi(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_sinpi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsinpi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_sinpi"]
+    pub fn sinpi(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_tanpi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ftanpi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_tanpi"]
+    pub fn tanpi(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_sin_cos`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsin_005fcos)
     #[link_name = "mpfr_sin_cos"]
     pub fn sin_cos(sop: mpfr_ptr, cop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
@@ -820,6 +1955,54 @@ extern "C" {
     /// See: [`mpfr_atan2`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fatan2)
     #[link_name = "mpfr_atan2"]
     pub fn atan2(rop: mpfr_ptr, y: mpfr_srcptr, x: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_acosu`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005facosu)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_acosu"]
+    pub fn acosu(rop: mpfr_ptr, op: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_asinu`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fasinu)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_asinu"]
+    pub fn asinu(rop: mpfr_ptr, op: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_atanu`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fatanu)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_atanu"]
+    pub fn atanu(rop: mpfr_ptr, op: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_atan2u`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fatan2u)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_atan2u"]
+    pub fn atan2u(rop: mpfr_ptr, y: mpfr_srcptr, x: mpfr_srcptr, u: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_acospi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005facospi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_acospi"]
+    pub fn acospi(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_asinpi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fasinpi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_asinpi"]
+    pub fn asinpi(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_atanpi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fatanpi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_atanpi"]
+    pub fn atanpi(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_atan2pi`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fatan2pi)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_atan2pi"]
+    pub fn atan2pi(rop: mpfr_ptr, y: mpfr_srcptr, x: mpfr_srcptr, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_cosh`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fcosh)
     #[link_name = "mpfr_cosh"]
     pub fn cosh(rop: mpfr_ptr, op: mpfr_srcptr, rnd: rnd_t) -> c_int;
@@ -975,6 +2158,18 @@ extern "C" {
     /// See: [`mpfr_sum`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fsum)
     #[link_name = "mpfr_sum"]
     pub fn sum(rop: mpfr_ptr, tab: *const mpfr_ptr, n: c_ulong, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_dot`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fdot)
+    ///
+    /// This function requires MPFR 4.1 or later.
+    #[cfg(mpfr_4_1)]
+    #[link_name = "mpfr_dot"]
+    pub fn dot(
+        rop: mpfr_ptr,
+        a: *const mpfr_ptr,
+        b: *const mpfr_ptr,
+        n: c_ulong,
+        rnd: rnd_t,
+    ) -> c_int;
 
     // Input and Output Functions
 
@@ -1069,6 +2264,12 @@ extern "C" {
     /// See: [`mpfr_fmod`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffmod)
     #[link_name = "mpfr_fmod"]
     pub fn fmod(r: mpfr_ptr, x: mpfr_srcptr, y: mpfr_srcptr, rnd: rnd_t) -> c_int;
+    /// See: [`mpfr_fmod_ui`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffmod_005fui)
+    ///
+    /// This function requires MPFR 4.2 or later.
+    #[cfg(mpfr_4_2)]
+    #[link_name = "mpfr_fmod_ui"]
+    pub fn fmod_ui(r: mpfr_ptr, x: mpfr_srcptr, y: c_ulong, rnd: rnd_t) -> c_int;
     /// See: [`mpfr_fmodquo`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005ffmodquo)
     #[link_name = "mpfr_fmodquo"]
     pub fn fmodquo(
@@ -1344,6 +2545,492 @@ extern "C" {
     /// See: [`mpfr_flags_restore`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fflags_005frestore)
     #[link_name = "mpfr_flags_restore"]
     pub fn flags_restore(flags: flags_t, mask: flags_t);
+}
+
+/// A set of MPFR exception flags.
+///
+/// The flags are stored as a mask of the `FLAGS_*` constants such as
+/// [`FLAGS_INEXACT`], and the set operators `|`, `&`, `^`, `-` and `!`
+/// are available. The methods that access the current flags use the
+/// flags of the current thread, as MPFR is built with thread-local
+/// storage.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, Flags};
+/// let flags = Flags::INEXACT | Flags::ERANGE;
+/// assert!(flags.contains(Flags::ERANGE));
+/// assert_eq!(flags.bits(), mpfr::FLAGS_INEXACT | mpfr::FLAGS_ERANGE);
+/// assert_eq!(format!("{:?}", flags), "Flags(INEXACT | ERANGE)");
+/// assert_eq!(!flags - Flags::NAN, Flags::UNDERFLOW | Flags::OVERFLOW | Flags::DIVBY0);
+/// ```
+///
+/// [`FLAGS_INEXACT`]: constant.FLAGS_INEXACT.html
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Flags {
+    bits: flags_t,
+}
+
+const FLAG_NAMES: [(flags_t, &str); 6] = [
+    (FLAGS_UNDERFLOW, "UNDERFLOW"),
+    (FLAGS_OVERFLOW, "OVERFLOW"),
+    (FLAGS_NAN, "NAN"),
+    (FLAGS_INEXACT, "INEXACT"),
+    (FLAGS_ERANGE, "ERANGE"),
+    (FLAGS_DIVBY0, "DIVBY0"),
+];
+
+impl Flags {
+    /// The underflow flag.
+    pub const UNDERFLOW: Flags = Flags {
+        bits: FLAGS_UNDERFLOW,
+    };
+    /// The overflow flag.
+    pub const OVERFLOW: Flags = Flags {
+        bits: FLAGS_OVERFLOW,
+    };
+    /// The NaN flag.
+    pub const NAN: Flags = Flags { bits: FLAGS_NAN };
+    /// The inexact flag.
+    pub const INEXACT: Flags = Flags {
+        bits: FLAGS_INEXACT,
+    };
+    /// The erange flag.
+    pub const ERANGE: Flags = Flags { bits: FLAGS_ERANGE };
+    /// The divide-by-zero flag.
+    pub const DIVBY0: Flags = Flags { bits: FLAGS_DIVBY0 };
+    /// All the flags.
+    pub const ALL: Flags = Flags { bits: FLAGS_ALL };
+
+    /// Returns an empty set of flags.
+    #[inline]
+    pub const fn empty() -> Flags {
+        Flags { bits: 0 }
+    }
+
+    /// Creates a set of flags from a mask, ignoring any bits that do
+    /// not correspond to a flag.
+    #[inline]
+    pub const fn from_bits(bits: flags_t) -> Flags {
+        Flags {
+            bits: bits & FLAGS_ALL,
+        }
+    }
+
+    /// Returns the flags as a mask.
+    #[inline]
+    pub const fn bits(self) -> flags_t {
+        self.bits
+    }
+
+    /// Returns [`true`] if no flags are in the set.
+    ///
+    /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.bits == 0
+    }
+
+    /// Returns [`true`] if all the flags in `other` are in the set.
+    ///
+    /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
+    #[inline]
+    pub const fn contains(self, other: Flags) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    /// Returns [`true`] if any of the flags in `other` are in the
+    /// set.
+    ///
+    /// [`true`]: https://doc.rust-lang.org/std/primitive.bool.html
+    #[inline]
+    pub const fn intersects(self, other: Flags) -> bool {
+        self.bits & other.bits != 0
+    }
+
+    /// Returns the flags that are currently raised.
+    ///
+    /// See: [`mpfr_flags_save`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fflags_005fsave)
+    #[inline]
+    pub fn current() -> Flags {
+        Flags::from_bits(unsafe { flags_save() })
+    }
+
+    /// Returns which of the flags in the set are currently raised.
+    ///
+    /// See: [`mpfr_flags_test`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fflags_005ftest)
+    #[inline]
+    pub fn test(self) -> Flags {
+        Flags::from_bits(unsafe { flags_test(self.bits) })
+    }
+
+    /// Raises the flags in the set, leaving the other flags
+    /// unchanged.
+    ///
+    /// See: [`mpfr_flags_set`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fflags_005fset)
+    #[inline]
+    pub fn raise(self) {
+        unsafe { flags_set(self.bits) }
+    }
+
+    /// Clears the flags in the set, leaving the other flags
+    /// unchanged.
+    ///
+    /// See: [`mpfr_flags_clear`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fflags_005fclear)
+    #[inline]
+    pub fn clear(self) {
+        unsafe { flags_clear(self.bits) }
+    }
+
+    /// Makes the current flags equal to the set, raising the flags
+    /// in the set and clearing all the others.
+    ///
+    /// See: [`mpfr_flags_restore`](https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#index-mpfr_005fflags_005frestore)
+    #[inline]
+    pub fn restore(self) {
+        unsafe { flags_restore(self.bits, FLAGS_ALL) }
+    }
+}
+
+impl Debug for Flags {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("Flags(")?;
+        if self.bits == 0 {
+            f.write_str("empty")?;
+        }
+        let mut sep = "";
+        for &(bit, name) in FLAG_NAMES.iter() {
+            if self.bits & bit != 0 {
+                f.write_str(sep)?;
+                f.write_str(name)?;
+                sep = " | ";
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+impl BitOr for Flags {
+    type Output = Flags;
+    #[inline]
+    fn bitor(self, rhs: Flags) -> Flags {
+        Flags {
+            bits: self.bits | rhs.bits,
+        }
+    }
+}
+
+impl BitOrAssign for Flags {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Flags) {
+        self.bits |= rhs.bits;
+    }
+}
+
+impl BitAnd for Flags {
+    type Output = Flags;
+    #[inline]
+    fn bitand(self, rhs: Flags) -> Flags {
+        Flags {
+            bits: self.bits & rhs.bits,
+        }
+    }
+}
+
+impl BitAndAssign for Flags {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Flags) {
+        self.bits &= rhs.bits;
+    }
+}
+
+impl BitXor for Flags {
+    type Output = Flags;
+    #[inline]
+    fn bitxor(self, rhs: Flags) -> Flags {
+        Flags {
+            bits: self.bits ^ rhs.bits,
+        }
+    }
+}
+
+impl BitXorAssign for Flags {
+    #[inline]
+    fn bitxor_assign(&mut self, rhs: Flags) {
+        self.bits ^= rhs.bits;
+    }
+}
+
+impl Sub for Flags {
+    type Output = Flags;
+    #[inline]
+    fn sub(self, rhs: Flags) -> Flags {
+        Flags {
+            bits: self.bits & !rhs.bits,
+        }
+    }
+}
+
+impl SubAssign for Flags {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Flags) {
+        self.bits &= !rhs.bits;
+    }
+}
+
+impl Not for Flags {
+    type Output = Flags;
+    #[inline]
+    fn not(self) -> Flags {
+        Flags {
+            bits: !self.bits & FLAGS_ALL,
+        }
+    }
+}
+
+/// A guard that saves the MPFR exception flags when created and
+/// restores them when dropped.
+///
+/// This makes it possible to call MPFR functions without clobbering
+/// the sticky flags of the caller. The flags are restored even if
+/// the scope is left because of a panic. As the flags are
+/// thread-local, the guard cannot be sent to another thread.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, Flags, FlagsGuard};
+/// Flags::ALL.clear();
+/// Flags::OVERFLOW.raise();
+/// {
+///     let guard = FlagsGuard::new();
+///     assert_eq!(guard.saved(), Flags::OVERFLOW);
+///     unsafe {
+///         mpfr::set_erangeflag();
+///     }
+///     assert_eq!(Flags::current(), Flags::OVERFLOW | Flags::ERANGE);
+/// }
+/// assert_eq!(Flags::current(), Flags::OVERFLOW);
+/// ```
+#[derive(Debug)]
+pub struct FlagsGuard {
+    saved: Flags,
+    // the flags are thread-local
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl FlagsGuard {
+    /// Saves the current flags, which will be restored when the
+    /// guard is dropped.
+    #[inline]
+    pub fn new() -> FlagsGuard {
+        FlagsGuard {
+            saved: Flags::current(),
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the flags that were saved when the guard was created.
+    #[inline]
+    pub fn saved(&self) -> Flags {
+        self.saved
+    }
+}
+
+impl Default for FlagsGuard {
+    #[inline]
+    fn default() -> FlagsGuard {
+        FlagsGuard::new()
+    }
+}
+
+impl Drop for FlagsGuard {
+    #[inline]
+    fn drop(&mut self) {
+        self.saved.restore();
+    }
+}
+
+/// An IEEE 754 binary interchange format that can be emulated using
+/// [`ExpRangeGuard::ieee`].
+///
+/// [`ExpRangeGuard::ieee`]: struct.ExpRangeGuard.html#method.ieee
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IeeeFormat {
+    /// The binary16 format.
+    Binary16,
+    /// The binary32 format, used by `f32`.
+    Binary32,
+    /// The binary64 format, used by `f64`.
+    Binary64,
+    /// The binary128 format.
+    Binary128,
+}
+
+impl IeeeFormat {
+    /// Returns the precision of the format, including the implicit
+    /// bit.
+    #[inline]
+    pub fn prec(self) -> prec_t {
+        match self {
+            IeeeFormat::Binary16 => 11,
+            IeeeFormat::Binary32 => 24,
+            IeeeFormat::Binary64 => 53,
+            IeeeFormat::Binary128 => 113,
+        }
+    }
+
+    /// Returns the maximum exponent of the format using the MPFR
+    /// convention, where the significand is in [0.5, 1).
+    #[inline]
+    pub fn emax(self) -> exp_t {
+        match self {
+            IeeeFormat::Binary16 => 16,
+            IeeeFormat::Binary32 => 128,
+            IeeeFormat::Binary64 => 1024,
+            IeeeFormat::Binary128 => 16384,
+        }
+    }
+
+    /// Returns the exponent of the smallest subnormal number of the
+    /// format using the MPFR convention, where the significand is in
+    /// [0.5, 1).
+    #[inline]
+    pub fn emin(self) -> exp_t {
+        4 - self.emax() - self.prec()
+    }
+}
+
+/// A guard that installs an exponent range and restores the previous
+/// exponent range when dropped.
+///
+/// The exponent range is restored even if the scope is left because
+/// of a panic. As the exponent range is thread-local, the guard
+/// cannot be sent to another thread. Guards can be nested, but they
+/// must be dropped in reverse order of creation.
+///
+/// Note that the values of existing variables are not modified when
+/// the exponent range changes; [`check_range`] can be used to bring
+/// them into the new range.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::mpfr::{self, ExpRangeGuard};
+/// unsafe {
+///     let (emin, emax) = (mpfr::get_emin(), mpfr::get_emax());
+///     {
+///         let _guard = ExpRangeGuard::new(-100, 100).unwrap();
+///         assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (-100, 100));
+///     }
+///     assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (emin, emax));
+/// }
+/// ```
+///
+/// [`check_range`]: fn.check_range.html
+#[derive(Debug)]
+pub struct ExpRangeGuard {
+    saved_emin: exp_t,
+    saved_emax: exp_t,
+    // the exponent range is thread-local
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl ExpRangeGuard {
+    /// Saves the current exponent range and installs the range from
+    /// `emin` to `emax`, which will be restored when the guard is
+    /// dropped.
+    ///
+    /// Returns [`None`] without modifying the exponent range if
+    /// either `emin` or `emax` is not supported by MPFR, or if `emin`
+    /// is greater than `emax`.
+    ///
+    /// [`None`]: https://doc.rust-lang.org/std/option/enum.Option.html#variant.None
+    pub fn new(emin: exp_t, emax: exp_t) -> Option<ExpRangeGuard> {
+        unsafe {
+            if emin > emax
+                || emin < get_emin_min()
+                || emin > get_emin_max()
+                || emax < get_emax_min()
+                || emax > get_emax_max()
+            {
+                return None;
+            }
+            let guard = ExpRangeGuard {
+                saved_emin: get_emin(),
+                saved_emax: get_emax(),
+                _not_send: PhantomData,
+            };
+            set_emin(emin);
+            set_emax(emax);
+            Some(guard)
+        }
+    }
+
+    /// Installs the exponent range of an IEEE 754 binary interchange
+    /// format.
+    ///
+    /// Numbers in the format can then be emulated exactly by using
+    /// [`IeeeFormat::prec`] as the precision, and by passing the
+    /// result of every operation through [`round`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gmp_mpfr_sys::mpfr::{self, ExpRangeGuard, IeeeFormat};
+    /// use std::mem;
+    /// unsafe {
+    ///     let format = IeeeFormat::Binary64;
+    ///     let guard = ExpRangeGuard::ieee(format);
+    ///     let mut f = mem::zeroed();
+    ///     mpfr::init2(&mut f, format.prec());
+    ///     // the smallest subnormal f64 divided by three
+    ///     mpfr::set_d(&mut f, 5e-324, mpfr::rnd_t::RNDN);
+    ///     let dir = mpfr::div_ui(&mut f, &f, 3, mpfr::rnd_t::RNDN);
+    ///     guard.round(&mut f, dir, mpfr::rnd_t::RNDN);
+    ///     assert_eq!(mpfr::get_d(&f, mpfr::rnd_t::RNDN), 5e-324 / 3.0);
+    ///     mpfr::clear(&mut f);
+    /// }
+    /// ```
+    ///
+    /// [`IeeeFormat::prec`]: enum.IeeeFormat.html#method.prec
+    /// [`round`]: #method.round
+    pub fn ieee(format: IeeeFormat) -> ExpRangeGuard {
+        ExpRangeGuard::new(format.emin(), format.emax()).expect("IEEE exponent range not supported")
+    }
+
+    /// Rounds `x` into the current exponent range, emulating
+    /// subnormal numbers as [`subnormalize`] does.
+    ///
+    /// `t` is the ternary value of the operation that computed `x`
+    /// with rounding mode `rnd`, and the new ternary value is
+    /// returned.
+    ///
+    /// # Safety
+    ///
+    /// `x` must point to an initialized [`mpfr_t`].
+    ///
+    /// [`mpfr_t`]: struct.mpfr_t.html
+    /// [`subnormalize`]: fn.subnormalize.html
+    #[inline]
+    pub unsafe fn round(&self, x: mpfr_ptr, t: c_int, rnd: rnd_t) -> c_int {
+        let t = check_range(x, t, rnd);
+        subnormalize(x, t, rnd)
+    }
+}
+
+impl Drop for ExpRangeGuard {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            set_emin(self.saved_emin);
+            set_emax(self.saved_emax);
+        }
+    }
+}
+
+extern "C" {
 
     // Compatibility with MPF
 
@@ -1439,9 +3126,171 @@ pub unsafe extern "C" fn custom_move(x: mpfr_ptr, new_position: *mut c_void) {
     (*x).d = new_position as *mut gmp::limb_t
 }
 
+/// An [`mpfr_t`] with a fixed maximum precision that stores its
+/// significand inline, so that it does not need any heap allocation.
+///
+/// The significand is stored in `LIMBS` limbs, so the precision can
+/// be at most [`MAX_PREC`]. The number is set up using the [custom
+/// interface], so it must not be cleared using [`clear`].
+///
+/// Since the value can be moved, the significand pointer inside the
+/// [`mpfr_t`] is updated by [`as_ptr`] and [`as_mut_ptr`], and the
+/// returned pointers are only valid until the value is moved.
+///
+/// The precision must only be changed using [`set_prec`], which
+/// refuses precisions that need more than `LIMBS` limbs. Passing the
+/// pointer to a function that reallocates the significand, that is
+/// [`mpfr::set_prec`], [`set_prec_raw`] with a precision larger than
+/// [`MAX_PREC`], [`clear`] or [`swap`], is undefined behavior.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// use gmp_mpfr_sys::mpfr::{self, FixedMpfr};
+/// # #[cfg(not(nails))] {
+/// let mut f = FixedMpfr::<2>::new(53);
+/// assert_eq!(FixedMpfr::<2>::MAX_PREC, 2 * gmp::NUMB_BITS as mpfr::prec_t);
+/// unsafe {
+///     mpfr::set_ui(f.as_mut_ptr(), 3, mpfr::rnd_t::RNDN);
+///     mpfr::sqrt(f.as_mut_ptr(), f.as_ptr(), mpfr::rnd_t::RNDN);
+///     // moving f is fine, as the pointer is updated when accessed
+///     let g = f;
+///     assert_eq!(mpfr::get_d(g.as_ptr(), mpfr::rnd_t::RNDN), 3f64.sqrt());
+/// }
+/// # }
+/// ```
+///
+/// [`MAX_PREC`]: #associatedconstant.MAX_PREC
+/// [`as_mut_ptr`]: #method.as_mut_ptr
+/// [`as_ptr`]: #method.as_ptr
+/// [`clear`]: fn.clear.html
+/// [`mpfr::set_prec`]: fn.set_prec.html
+/// [`mpfr_t`]: struct.mpfr_t.html
+/// [`set_prec_raw`]: fn.set_prec_raw.html
+/// [`set_prec`]: #method.set_prec
+/// [`swap`]: fn.swap.html
+/// [custom interface]: https://tspiteri.gitlab.io/gmp-mpfr-sys/mpfr/MPFR-Interface.html#Custom-Interface
+#[derive(Debug)]
+pub struct FixedMpfr<const LIMBS: usize> {
+    raw: UnsafeCell<mpfr_t>,
+    // MPFR writes through the significand pointer set by as_ptr, so
+    // the limbs must be in an UnsafeCell too
+    limbs: UnsafeCell<[gmp::limb_t; LIMBS]>,
+}
+
+impl<const LIMBS: usize> FixedMpfr<LIMBS> {
+    /// The maximum precision that fits in `LIMBS` limbs.
+    pub const MAX_PREC: prec_t = LIMBS as prec_t * gmp::NUMB_BITS as prec_t;
+
+    /// Creates a number with precision `prec` and a value of NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prec` is less than [`PREC_MIN`] or greater than
+    /// [`MAX_PREC`].
+    ///
+    /// [`MAX_PREC`]: #associatedconstant.MAX_PREC
+    /// [`PREC_MIN`]: constant.PREC_MIN.html
+    pub fn new(prec: prec_t) -> FixedMpfr<LIMBS> {
+        Self::check_prec(prec);
+        let mut limbs = [0; LIMBS];
+        let mut raw = mpfr_t {
+            prec: 0,
+            sign: 0,
+            exp: 0,
+            d: ptr::null_mut(),
+        };
+        unsafe {
+            let significand = limbs.as_mut_ptr() as *mut c_void;
+            custom_init(significand, prec);
+            custom_init_set(&mut raw, NAN_KIND, 0, prec, significand);
+        }
+        FixedMpfr {
+            raw: UnsafeCell::new(raw),
+            limbs: UnsafeCell::new(limbs),
+        }
+    }
+
+    fn check_prec(prec: prec_t) {
+        assert!(
+            prec >= PREC_MIN && prec <= Self::MAX_PREC,
+            "precision {} not in range {}..={}",
+            prec,
+            PREC_MIN,
+            Self::MAX_PREC
+        );
+    }
+
+    /// Returns the precision.
+    #[inline]
+    pub fn prec(&self) -> prec_t {
+        unsafe { (*self.raw.get()).prec }
+    }
+
+    /// Sets the precision to `prec` and the value to NaN, like
+    /// [`mpfr::set_prec`] but without reallocating the significand.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prec` is less than [`PREC_MIN`] or greater than
+    /// [`MAX_PREC`].
+    ///
+    /// [`MAX_PREC`]: #associatedconstant.MAX_PREC
+    /// [`PREC_MIN`]: constant.PREC_MIN.html
+    /// [`mpfr::set_prec`]: fn.set_prec.html
+    pub fn set_prec(&mut self, prec: prec_t) {
+        Self::check_prec(prec);
+        unsafe {
+            let significand = self.limbs.get_mut().as_mut_ptr() as *mut c_void;
+            custom_init(significand, prec);
+            custom_init_set(self.raw.get_mut(), NAN_KIND, 0, prec, significand);
+        }
+    }
+
+    /// Returns a pointer to the inner [`mpfr_t`] for reading, which
+    /// is valid until `self` is moved or mutated.
+    ///
+    /// [`mpfr_t`]: struct.mpfr_t.html
+    #[inline]
+    pub fn as_ptr(&self) -> *const mpfr_t {
+        let raw = self.raw.get();
+        unsafe {
+            custom_move(raw, self.limbs.get() as *mut c_void);
+        }
+        raw
+    }
+
+    /// Returns a pointer to the inner [`mpfr_t`] for reading and
+    /// writing, which is valid until `self` is moved.
+    ///
+    /// [`mpfr_t`]: struct.mpfr_t.html
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut mpfr_t {
+        let significand = self.limbs.get_mut().as_mut_ptr() as *mut c_void;
+        let raw = self.raw.get_mut();
+        unsafe {
+            custom_move(raw, significand);
+        }
+        raw
+    }
+}
+
+impl<const LIMBS: usize> Clone for FixedMpfr<LIMBS> {
+    #[inline]
+    fn clone(&self) -> FixedMpfr<LIMBS> {
+        FixedMpfr {
+            raw: UnsafeCell::new(unsafe { *self.raw.get() }),
+            limbs: UnsafeCell::new(unsafe { *self.limbs.get() }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use mpfr;
+    use std::mem;
+    use std::os::raw::c_int;
 
     #[cfg(not(newer_cache))]
     #[test]
@@ -1463,27 +3312,816 @@ mod tests {
     }
 
     #[test]
-    fn check_round_nearest_away() {
+    fn check_get_string() {
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 53);
+            mpfr::set_d(&mut f, 0.1, mpfr::rnd_t::RNDN);
+            let (digits, exp) = mpfr::get_string(10, 0, &f, mpfr::rnd_t::RNDN);
+            assert_eq!(digits, "10000000000000001");
+            assert_eq!(exp, 0);
+            mpfr::set_inf(&mut f, -1);
+            let inf = mpfr::get_string(10, 1, &f, mpfr::rnd_t::RNDN);
+            assert_eq!(inf.0, "-@Inf@");
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_display() {
+        use mpfr::DisplayMpfr;
+        unsafe {
+            let mut f = mpfr::new2(53);
+            assert_eq!(format!("{:>5}", DisplayMpfr::new(&f)), "  NaN");
+            assert_eq!(format!("{:.3}", DisplayMpfr::new(&f)), "NaN");
+            assert_eq!(
+                format!("{:?}", DisplayMpfr::new(&f)),
+                "mpfr_t { prec: 53, kind: NaN, sign: 1, value: NaN }"
+            );
+            mpfr::set_inf(&mut f, -1);
+            assert_eq!(format!("{:^6}", DisplayMpfr::new(&f)), " -inf ");
+            assert_eq!(
+                format!("{:?}", DisplayMpfr::new(&f)),
+                "mpfr_t { prec: 53, kind: Inf, sign: -1, value: -inf }"
+            );
+            mpfr::set_zero(&mut f, -1);
+            assert_eq!(format!("{:.1}", DisplayMpfr::new(&f)), "-0.0e0");
+            assert_eq!(
+                format!("{:?}", DisplayMpfr::new(&f)),
+                "mpfr_t { prec: 53, kind: Zero, sign: -1, value: -0e0 }"
+            );
+            mpfr::set_d(&mut f, 0.1, mpfr::rnd_t::RNDN);
+            assert_eq!(format!("{}", DisplayMpfr::new(&f)), "1.0000000000000001e-1");
+            assert_eq!(format!("{:.3}", DisplayMpfr::new(&f)), "1.000e-1");
+            assert_eq!(format!("{:+012.2}", DisplayMpfr::new(&f)), "+00001.00e-1");
+            mpfr::set_d(&mut f, 9.96, mpfr::rnd_t::RNDN);
+            assert_eq!(format!("{:.1}", DisplayMpfr::new(&f)), "1.0e1");
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_128_bit() {
+        use gmp;
+        let modes = [
+            mpfr::rnd_t::RNDN,
+            mpfr::rnd_t::RNDZ,
+            mpfr::rnd_t::RNDU,
+            mpfr::rnd_t::RNDD,
+            mpfr::rnd_t::RNDA,
+        ];
+        let values = [
+            0,
+            1,
+            -1,
+            255,
+            257,
+            -257,
+            i128::MAX,
+            i128::MIN,
+            i128::MIN + 1,
+        ];
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 8);
+            let mut g = mem::zeroed();
+            mpfr::init2(&mut g, 8);
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            for &rnd in &modes {
+                for &i in &values {
+                    gmp::mpz_set_i128(&mut z, i);
+                    let dir = mpfr::set_i128(&mut f, i, rnd);
+                    let expected = mpfr::set_z(&mut g, &z, rnd);
+                    assert_eq!(dir.signum(), expected.signum());
+                    assert_eq!(mpfr::cmp(&f, &g), 0);
+                }
+                gmp::mpz_set_u128(&mut z, u128::MAX);
+                let dir = mpfr::set_u128(&mut f, u128::MAX, rnd);
+                let expected = mpfr::set_z(&mut g, &z, rnd);
+                assert_eq!(dir.signum(), expected.signum());
+                assert_eq!(mpfr::cmp(&f, &g), 0);
+            }
+            gmp::mpz_clear(&mut z);
+            mpfr::clear(&mut g);
+
+            mpfr::set_prec(&mut f, 130);
+            // 2^127 - 0.5
+            mpfr::set_ui_2exp(&mut f, 1, 127, mpfr::rnd_t::RNDN);
+            let fp: *mut mpfr::mpfr_t = &mut f;
+            mpfr::sub_d(fp, fp, 0.5, mpfr::rnd_t::RNDN);
+            mpfr::clear_flags();
+            assert_eq!(mpfr::fits_i128_p(&f, mpfr::rnd_t::RNDZ), 1);
+            assert_eq!(mpfr::fits_i128_p(&f, mpfr::rnd_t::RNDU), 0);
+            assert_eq!(mpfr::flags_save(), 0);
+            assert_eq!(mpfr::get_i128(&f, mpfr::rnd_t::RNDZ), i128::MAX);
+            assert!(mpfr::inexflag_p() != 0 && mpfr::erangeflag_p() == 0);
+            assert_eq!(mpfr::get_u128(&f, mpfr::rnd_t::RNDU), 1 << 127);
+            mpfr::clear_flags();
+            assert_eq!(mpfr::get_i128(&f, mpfr::rnd_t::RNDU), i128::MAX);
+            assert!(mpfr::erangeflag_p() != 0);
+            mpfr::neg(fp, fp, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::get_i128(&f, mpfr::rnd_t::RNDD), i128::MIN);
+            assert_eq!(mpfr::get_i128(&f, mpfr::rnd_t::RNDZ), i128::MIN + 1);
+            mpfr::clear_flags();
+            assert_eq!(mpfr::get_u128(&f, mpfr::rnd_t::RNDZ), 0);
+            assert!(mpfr::erangeflag_p() != 0);
+            mpfr::set_d(&mut f, -0.5, mpfr::rnd_t::RNDN);
+            mpfr::clear_flags();
+            assert_eq!(mpfr::get_u128(&f, mpfr::rnd_t::RNDZ), 0);
+            assert!(mpfr::inexflag_p() != 0 && mpfr::erangeflag_p() == 0);
+            mpfr::set_ui_2exp(&mut f, 1, 128, mpfr::rnd_t::RNDN);
+            mpfr::clear_flags();
+            assert_eq!(mpfr::get_u128(&f, mpfr::rnd_t::RNDN), u128::MAX);
+            assert!(mpfr::erangeflag_p() != 0);
+            mpfr::set_nan(&mut f);
+            mpfr::clear_flags();
+            assert_eq!(mpfr::get_i128(&f, mpfr::rnd_t::RNDN), 0);
+            assert!(mpfr::erangeflag_p() != 0);
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_try_get() {
+        use gmp::ConversionError::*;
+        use mpfr::Flags;
+        unsafe {
+            let mut f = mpfr::new2(130);
+            Flags::clear(Flags::ALL);
+            assert_eq!(mpfr::try_get_i32(&f), Err(Nan));
+            mpfr::set_inf(&mut f, -1);
+            assert_eq!(mpfr::try_get_i128(&f), Err(Overflow));
+            assert_eq!(
+                mpfr::try_get_i128_round(&f, mpfr::rnd_t::RNDN),
+                Err(Overflow)
+            );
+            mpfr::set_d(&mut f, 2.5, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_u8(&f), Err(NotInteger));
+            assert_eq!(mpfr::try_get_u8_round(&f, mpfr::rnd_t::RNDZ), Err(Inexact));
+            mpfr::set_d(&mut f, 255.5, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_u8_round(&f, mpfr::rnd_t::RNDD), Err(Inexact));
+            assert_eq!(mpfr::try_get_u8_round(&f, mpfr::rnd_t::RNDU), Err(Overflow));
+            mpfr::set_d(&mut f, -0.5, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_u32_round(&f, mpfr::rnd_t::RNDZ), Err(Inexact));
+            assert_eq!(
+                mpfr::try_get_u32_round(&f, mpfr::rnd_t::RNDD),
+                Err(Overflow)
+            );
+            mpfr::set_zero(&mut f, -1);
+            assert_eq!(mpfr::try_get_u8(&f), Ok(0));
+            mpfr::set_si(&mut f, 300, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_u8(&f), Err(Overflow));
+            assert_eq!(mpfr::try_get_i16(&f), Ok(300));
+            assert_eq!(mpfr::try_get_i16_round(&f, mpfr::rnd_t::RNDN), Ok(300));
+            assert_eq!(mpfr::try_get_usize(&f), Ok(300));
+            mpfr::set_si(&mut f, -300, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_u64(&f), Err(Overflow));
+            assert_eq!(mpfr::try_get_isize(&f), Ok(-300));
+            mpfr::set_ui_2exp(&mut f, 1, 127, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_u128(&f), Ok(1 << 127));
+            assert_eq!(mpfr::try_get_i128(&f), Err(Overflow));
+            assert_eq!(mpfr::try_get_u64(&f), Err(Overflow));
+            let fp: *mut mpfr::mpfr_t = &mut f;
+            mpfr::neg(fp, fp, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_i128(&f), Ok(i128::MIN));
+            mpfr::sub_ui(fp, fp, 1, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::try_get_i128(&f), Err(Overflow));
+            mpfr::add_d(fp, fp, 0.5, mpfr::rnd_t::RNDN);
+            assert_eq!(
+                mpfr::try_get_i128_round(&f, mpfr::rnd_t::RNDU),
+                Err(Inexact)
+            );
+            assert_eq!(
+                mpfr::try_get_i128_round(&f, mpfr::rnd_t::RNDD),
+                Err(Overflow)
+            );
+            assert!(Flags::current().is_empty());
+            mpfr::clear(&mut f);
+        }
+    }
+
+    // Checks conversions for all bit patterns of a 16-bit format with
+    // the given number of exponent bits. Every finite pattern must
+    // survive a round trip, and values a quarter, a half and three
+    // quarters of the way to the next pattern must be rounded
+    // correctly in all rounding modes.
+    fn check_16_bit(
+        set: unsafe fn(*mut mpfr::mpfr_t, u16, mpfr::rnd_t) -> c_int,
+        get: unsafe fn(*const mpfr::mpfr_t, mpfr::rnd_t) -> u16,
+        exp_bits: u32,
+    ) {
+        use mpfr::rnd_t;
+        let man_bits = 15 - exp_bits;
+        let inf = (((1u32 << exp_bits) - 1) << man_bits) as u16;
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 53);
+            let mut g = mem::zeroed();
+            mpfr::init2(&mut g, 53);
+            let mut point = mem::zeroed();
+            mpfr::init2(&mut point, 53);
+            let mut narrow = mem::zeroed();
+            mpfr::init2(&mut narrow, 4);
+            for bits in 0..=u16::MAX {
+                let abs = bits & 0x7fff;
+                let neg = abs != bits;
+                let dir = set(&mut f, bits, rnd_t::RNDN);
+                assert_eq!(dir, 0);
+                if abs > inf {
+                    assert!(mpfr::nan_p(&f) != 0);
+                    assert_eq!(get(&f, rnd_t::RNDN), inf | (1 << (man_bits - 1)));
+                    continue;
+                }
+                assert_eq!((mpfr::signbit(&f) != 0), neg);
+                for &rnd in &[
+                    rnd_t::RNDN,
+                    rnd_t::RNDZ,
+                    rnd_t::RNDU,
+                    rnd_t::RNDD,
+                    rnd_t::RNDA,
+                ] {
+                    assert_eq!(get(&f, rnd), bits);
+                    let dir = set(&mut narrow, bits, rnd);
+                    assert_eq!(dir.signum(), mpfr::cmp(&narrow, &f).signum());
+                }
+                if abs >= inf {
+                    continue;
+                }
+                let next = bits + 1;
+                if abs + 1 == inf {
+                    // use 2^(bias + 1) instead of infinity
+                    let one = if neg { -1 } else { 1 };
+                    mpfr::set_si_2exp(&mut g, one, 1 << (exp_bits - 1), rnd_t::RNDN);
+                } else {
+                    set(&mut g, next, rnd_t::RNDN);
+                }
+                let even = if bits & 1 == 0 { bits } else { next };
+                let (up, down) = if neg { (bits, next) } else { (next, bits) };
+                for &(num, nearest) in &[(1, bits), (2, even), (3, next)] {
+                    // f + (g - f) * num / 4, which is exact in 53 bits
+                    let p: *mut mpfr::mpfr_t = &mut point;
+                    mpfr::sub(p, &g, &f, rnd_t::RNDN);
+                    mpfr::mul_ui(p, p, num, rnd_t::RNDN);
+                    mpfr::div_2ui(p, p, 2, rnd_t::RNDN);
+                    mpfr::add(p, p, &f, rnd_t::RNDN);
+                    assert_eq!(get(p, rnd_t::RNDN), nearest);
+                    assert_eq!(get(p, rnd_t::RNDZ), bits);
+                    assert_eq!(get(p, rnd_t::RNDU), up);
+                    assert_eq!(get(p, rnd_t::RNDD), down);
+                    assert_eq!(get(p, rnd_t::RNDA), next);
+                }
+            }
+            mpfr::clear(&mut narrow);
+            mpfr::clear(&mut point);
+            mpfr::clear(&mut g);
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[test]
+    fn check_f16_bits() {
+        check_16_bit(mpfr::set_f16_bits, mpfr::get_f16_bits, 5);
+    }
+
+    #[test]
+    fn check_bf16_bits() {
+        check_16_bit(mpfr::set_bf16_bits, mpfr::get_bf16_bits, 8);
         unsafe {
-            let mut f;
-            #[cfg(maybe_uninit)]
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 24);
+            for bits in 0..=u16::MAX {
+                mpfr::set_bf16_bits(&mut f, bits, mpfr::rnd_t::RNDN);
+                let single = f32::from_bits(u32::from(bits) << 16);
+                if single.is_nan() {
+                    assert!(mpfr::nan_p(&f) != 0);
+                } else {
+                    let back = mpfr::get_flt(&f, mpfr::rnd_t::RNDN);
+                    assert_eq!(back.to_bits(), single.to_bits());
+                }
+            }
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_os = "linux"))]
+    #[test]
+    fn check_long_double() {
+        use mpfr::long_double;
+        use std::os::raw::{c_char, c_long};
+        #[link(name = "gmp_mpfr_sys_ld_test", kind = "static")]
+        extern "C" {
+            fn gmp_mpfr_sys_test_strtold(rop: *mut long_double, s: *const c_char);
+            fn gmp_mpfr_sys_test_ld_eq(op1: *const long_double, op2: *const long_double) -> c_int;
+        }
+        unsafe fn strtold(s: &str) -> long_double {
+            let mut ld = long_double::default();
+            gmp_mpfr_sys_test_strtold(&mut ld, format!("{}\0", s).as_ptr() as _);
+            ld
+        }
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 64);
+            let mut g = mem::zeroed();
+            mpfr::init2(&mut g, 200);
+            // the first needs all 64 bits, the second is subnormal
+            for s in &["0xfedcba9876543211p+40", "-0x3p-16444"] {
+                let ld = strtold(s);
+                assert_eq!(mpfr::set_ld(&mut f, ld, mpfr::rnd_t::RNDN), 0);
+                mpfr::set_str(
+                    &mut g,
+                    format!("{}\0", s).as_ptr() as _,
+                    0,
+                    mpfr::rnd_t::RNDN,
+                );
+                assert_eq!(mpfr::cmp(&f, &g), 0);
+                assert_eq!(mpfr::cmp_ld(&g, ld), 0);
+                let back = mpfr::get_ld(&f, mpfr::rnd_t::RNDN);
+                assert_ne!(gmp_mpfr_sys_test_ld_eq(&back, &ld), 0);
+            }
+            // 0.1 is rounded by both strtold and mpfr_get_ld
+            mpfr::set_str(&mut g, b"0.1\0".as_ptr() as _, 10, mpfr::rnd_t::RNDN);
+            let tenth = strtold("0.1");
+            let rounded = mpfr::get_ld(&g, mpfr::rnd_t::RNDN);
+            assert_ne!(gmp_mpfr_sys_test_ld_eq(&rounded, &tenth), 0);
+            assert!(mpfr::cmp_ld(&g, tenth) != 0);
+            let below = mpfr::get_ld(&g, mpfr::rnd_t::RNDZ);
+            assert!(mpfr::cmp_ld(&g, below) > 0);
+
+            mpfr::set_ui(&mut f, 3, mpfr::rnd_t::RNDN);
+            let mut exp: c_long = 0;
+            let frac = mpfr::get_ld_2exp(&mut exp, &f, mpfr::rnd_t::RNDN);
+            assert_eq!(exp, 2);
+            assert_ne!(gmp_mpfr_sys_test_ld_eq(&frac, &strtold("0.75")), 0);
+            mpfr::clear(&mut g);
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[cfg(feature = "float128")]
+    #[test]
+    fn check_float128() {
+        use mpfr::rnd_t;
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 113);
+            let encodings: [(u128, &[u8]); 6] = [
+                (0x3fff_0000_0000_0000_0000_0000_0000_0000, b"1\0"),
+                (0xc000_0000_0000_0000_0000_0000_0000_0000, b"-2\0"),
+                (0x0000_0000_0000_0000_0000_0000_0000_0001, b"0x1p-16494\0"),
+                (
+                    0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff,
+                    b"0x1.ffffffffffffffffffffffffffffp+16383\0",
+                ),
+                (0x8000_0000_0000_0000_0000_0000_0000_0000, b"-0\0"),
+                (0x7fff_0000_0000_0000_0000_0000_0000_0000, b"@inf@\0"),
+            ];
+            for &(bits, s) in &encodings {
+                mpfr::set_str(&mut f, s.as_ptr() as _, 0, rnd_t::RNDN);
+                assert_eq!(mpfr::get_float128(&f, rnd_t::RNDN), bits);
+                mpfr::set_nan(&mut f);
+                assert_eq!(mpfr::set_float128(&mut f, bits, rnd_t::RNDN), 0);
+                assert_eq!(mpfr::get_float128(&f, rnd_t::RNDN), bits);
+            }
+
+            mpfr::set_float128(
+                &mut f,
+                0x7fff_8000_0000_0000_0000_0000_0000_0000,
+                rnd_t::RNDN,
+            );
+            assert!(mpfr::nan_p(&f) != 0);
+
+            mpfr::const_pi(&mut f, rnd_t::RNDN);
+            assert_eq!(
+                mpfr::get_float128(&f, rnd_t::RNDN),
+                0x4000_921f_b544_42d1_8469_898c_c517_01b8
+            );
+            mpfr::set_prec(&mut f, 200);
+            mpfr::set_ui(&mut f, 1, rnd_t::RNDN);
+            let fp: *mut mpfr::mpfr_t = &mut f;
+            mpfr::div_ui(fp, fp, 3, rnd_t::RNDN);
+            assert_eq!(
+                mpfr::get_float128(&f, rnd_t::RNDN),
+                0x3ffd_5555_5555_5555_5555_5555_5555_5555
+            );
+            assert_eq!(
+                mpfr::get_float128(&f, rnd_t::RNDU),
+                0x3ffd_5555_5555_5555_5555_5555_5555_5556
+            );
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[cfg(all(feature = "decimal-float", target_arch = "x86_64"))]
+    #[test]
+    fn check_decimal64() {
+        use mpfr::rnd_t;
+        unsafe {
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 64);
+            let mut g = mem::zeroed();
+            mpfr::init2(&mut g, 64);
+            // BID encodings; the results of get_decimal64 always have
+            // 16 significant digits
+            let encodings: [(u64, &[u8], u64); 5] = [
+                (0x31c0_0000_0000_0001, b"1\0", 0x2fe3_8d7e_a4c6_8000),
+                (0xb1c0_0000_0000_000c, b"-12\0", 0xb004_4364_c5bb_0000),
+                (0x31a0_0000_0000_0001, b"0.1\0", 0x2fc3_8d7e_a4c6_8000),
+                (0x7800_0000_0000_0000, b"@inf@\0", 0x7800_0000_0000_0000),
+                (0xf800_0000_0000_0000, b"-@inf@\0", 0xf800_0000_0000_0000),
+            ];
+            for &(bits, s, normalized) in &encodings {
+                mpfr::set_str(&mut g, s.as_ptr() as _, 10, rnd_t::RNDN);
+                mpfr::set_decimal64(&mut f, bits, rnd_t::RNDN);
+                assert_ne!(mpfr::equal_p(&f, &g), 0);
+                mpfr::set_decimal64(&mut f, normalized, rnd_t::RNDN);
+                assert_ne!(mpfr::equal_p(&f, &g), 0);
+                assert_eq!(mpfr::get_decimal64(&g, rnd_t::RNDN), normalized);
+            }
+
+            for &zero in &[0x31c0_0000_0000_0000, 0xb1c0_0000_0000_0000u64] {
+                mpfr::set_decimal64(&mut f, zero, rnd_t::RNDN);
+                assert_ne!(mpfr::zero_p(&f), 0);
+                assert_eq!(mpfr::signbit(&f) != 0, zero >> 63 != 0);
+                let bits = mpfr::get_decimal64(&f, rnd_t::RNDN);
+                assert_eq!(bits >> 63, zero >> 63);
+                mpfr::set_decimal64(&mut g, bits, rnd_t::RNDN);
+                assert_ne!(mpfr::zero_p(&g), 0);
+            }
+
+            // quiet NaN
+            let nan = 0x7c00_0000_0000_0000;
+            mpfr::set_decimal64(&mut f, nan, rnd_t::RNDN);
+            assert_ne!(mpfr::nan_p(&f), 0);
+            assert_eq!(mpfr::get_decimal64(&f, rnd_t::RNDN) & nan, nan);
+
+            // 9_999_999_999_999_999 × 10^369, the largest finite value
+            let max = 0x77fb_86f2_6fc0_ffff;
+            mpfr::set_decimal64(&mut f, max, rnd_t::RNDN);
+            assert_eq!(mpfr::get_decimal64(&f, rnd_t::RNDN), max);
+            assert_eq!(mpfr::get_decimal64(&f, rnd_t::RNDU), max);
+
+            // 2/3 is 6_666_666_666_666_667 × 10^−16 when rounded
+            mpfr::set_ui(&mut f, 2, rnd_t::RNDN);
+            let fp: *mut mpfr::mpfr_t = &mut f;
+            mpfr::div_ui(fp, fp, 3, rnd_t::RNDN);
+            assert_eq!(mpfr::get_decimal64(&f, rnd_t::RNDN), 0x2fd7_af4c_4a80_aaab);
+            assert_eq!(mpfr::get_decimal64(&f, rnd_t::RNDZ), 0x2fd7_af4c_4a80_aaaa);
+            mpfr::clear(&mut g);
+            mpfr::clear(&mut f);
+        }
+    }
+
+    #[cfg(mpfr_4_1)]
+    #[test]
+    fn check_mpfr_4_1() {
+        use mpfr::rnd_t;
+        unsafe {
+            // 53 bits need 17 decimal digits
+            assert_eq!(mpfr::get_str_ndigits(10, 53), 17);
+
+            let mut a = mem::zeroed();
+            mpfr::init2(&mut a, 53);
+            let mut b = mem::zeroed();
+            mpfr::init2(&mut b, 53);
+            let mut r = mem::zeroed();
+            mpfr::init2(&mut r, 53);
+
+            mpfr::set_si(&mut a, -3, rnd_t::RNDN);
+            assert!(mpfr::cmpabs_ui(&a, 2) > 0);
+            assert_eq!(mpfr::cmpabs_ui(&a, 3), 0);
+
+            // −0 is ordered before +0
+            mpfr::set_zero(&mut a, -1);
+            mpfr::set_zero(&mut b, 1);
+            assert_ne!(mpfr::total_order_p(&a, &b), 0);
+            assert_eq!(mpfr::total_order_p(&b, &a), 0);
+
+            // (2, 3) · (4, 5) = 23
+            mpfr::set_ui(&mut a, 2, rnd_t::RNDN);
+            mpfr::set_ui(&mut b, 3, rnd_t::RNDN);
+            let mut c = mem::zeroed();
+            mpfr::init_set_ui(&mut c, 4, rnd_t::RNDN);
+            let mut d = mem::zeroed();
+            mpfr::init_set_ui(&mut d, 5, rnd_t::RNDN);
+            let x: [*mut mpfr::mpfr_t; 2] = [&mut a, &mut b];
+            let y: [*mut mpfr::mpfr_t; 2] = [&mut c, &mut d];
+            assert_eq!(mpfr::dot(&mut r, x.as_ptr(), y.as_ptr(), 2, rnd_t::RNDN), 0);
+            assert_eq!(mpfr::cmp_ui(&r, 23), 0);
+
+            mpfr::clear(&mut d);
+            mpfr::clear(&mut c);
+            mpfr::clear(&mut r);
+            mpfr::clear(&mut b);
+            mpfr::clear(&mut a);
+        }
+    }
+
+    #[cfg(mpfr_4_2)]
+    #[test]
+    fn check_mpfr_4_2() {
+        use mpfr::rnd_t;
+        unsafe {
+            let mut x = mem::zeroed();
+            mpfr::init2(&mut x, 53);
+            let mut y = mem::zeroed();
+            mpfr::init2(&mut y, 53);
+            let mut r = mem::zeroed();
+            mpfr::init2(&mut r, 53);
+            let check = |r: &mpfr::mpfr_t, expected: f64| {
+                assert_eq!(mpfr::get_d(r, rnd_t::RNDN), expected);
+            };
+
+            mpfr::set_ui(&mut x, 3, rnd_t::RNDN);
+            mpfr::exp2m1(&mut r, &x, rnd_t::RNDN);
+            check(&r, 7.0);
+            mpfr::exp10m1(&mut r, &x, rnd_t::RNDN);
+            check(&r, 999.0);
+            mpfr::log2p1(&mut r, &x, rnd_t::RNDN);
+            check(&r, 2.0);
+            mpfr::set_ui(&mut x, 99, rnd_t::RNDN);
+            mpfr::log10p1(&mut r, &x, rnd_t::RNDN);
+            check(&r, 2.0);
+            // (1 + 1)^3
+            mpfr::set_ui(&mut x, 1, rnd_t::RNDN);
+            mpfr::compound_si(&mut r, &x, 3, rnd_t::RNDN);
+            check(&r, 8.0);
+
+            // angles in half-turns and in units of 1/360 of a turn
+            mpfr::set_d(&mut x, 0.5, rnd_t::RNDN);
+            mpfr::sinpi(&mut r, &x, rnd_t::RNDN);
+            check(&r, 1.0);
+            mpfr::cospi(&mut r, &x, rnd_t::RNDN);
+            check(&r, 0.0);
+            mpfr::set_d(&mut x, 0.25, rnd_t::RNDN);
+            mpfr::tanpi(&mut r, &x, rnd_t::RNDN);
+            check(&r, 1.0);
+            mpfr::set_ui(&mut x, 90, rnd_t::RNDN);
+            mpfr::sinu(&mut r, &x, 360, rnd_t::RNDN);
+            check(&r, 1.0);
+            mpfr::cosu(&mut r, &x, 360, rnd_t::RNDN);
+            check(&r, 0.0);
+            mpfr::set_ui(&mut x, 45, rnd_t::RNDN);
+            mpfr::tanu(&mut r, &x, 360, rnd_t::RNDN);
+            check(&r, 1.0);
+            mpfr::set_ui(&mut x, 1, rnd_t::RNDN);
+            mpfr::asinu(&mut r, &x, 360, rnd_t::RNDN);
+            check(&r, 90.0);
+            mpfr::acosu(&mut r, &x, 360, rnd_t::RNDN);
+            check(&r, 0.0);
+            mpfr::atanu(&mut r, &x, 360, rnd_t::RNDN);
+            check(&r, 45.0);
+            mpfr::asinpi(&mut r, &x, rnd_t::RNDN);
+            check(&r, 0.5);
+            mpfr::acospi(&mut r, &x, rnd_t::RNDN);
+            check(&r, 0.0);
+            mpfr::atanpi(&mut r, &x, rnd_t::RNDN);
+            check(&r, 0.25);
+            mpfr::set_si(&mut y, -1, rnd_t::RNDN);
+            mpfr::atan2u(&mut r, &y, &x, 360, rnd_t::RNDN);
+            check(&r, -45.0);
+            mpfr::atan2pi(&mut r, &y, &x, rnd_t::RNDN);
+            check(&r, -0.25);
+
+            mpfr::set_ui(&mut x, 4, rnd_t::RNDN);
+            mpfr::set_d(&mut y, 0.5, rnd_t::RNDN);
+            mpfr::powr(&mut r, &x, &y, rnd_t::RNDN);
+            check(&r, 2.0);
+            mpfr::pown(&mut r, &x, -1, rnd_t::RNDN);
+            check(&r, 0.25);
+            mpfr::pow_uj(&mut r, &x, 3, rnd_t::RNDN);
+            check(&r, 64.0);
+            mpfr::pow_sj(&mut r, &x, -2, rnd_t::RNDN);
+            check(&r, 0.0625);
+            mpfr::rootn_si(&mut r, &x, -2, rnd_t::RNDN);
+            check(&r, 0.5);
+            mpfr::set_ui(&mut x, 17, rnd_t::RNDN);
+            mpfr::fmod_ui(&mut r, &x, 5, rnd_t::RNDN);
+            check(&r, 2.0);
+
+            mpfr::clear(&mut r);
+            mpfr::clear(&mut y);
+            mpfr::clear(&mut x);
+        }
+    }
+
+    #[test]
+    fn check_flags() {
+        use mpfr::{Flags, FlagsGuard};
+        use std::panic;
+
+        let mut flags = Flags::empty();
+        assert_eq!(format!("{:?}", flags), "Flags(empty)");
+        flags |= Flags::DIVBY0 | Flags::UNDERFLOW;
+        assert_eq!(format!("{:?}", flags), "Flags(UNDERFLOW | DIVBY0)");
+        assert_eq!(!flags | flags, Flags::ALL);
+        assert_eq!(Flags::from_bits(!0), Flags::ALL);
+        assert!(flags.intersects(Flags::DIVBY0 | Flags::NAN));
+        assert!(!flags.contains(Flags::DIVBY0 | Flags::NAN));
+
+        Flags::ALL.clear();
+        flags.restore();
+        assert_eq!(Flags::current(), flags);
+        assert_eq!((Flags::NAN | Flags::UNDERFLOW).test(), Flags::UNDERFLOW);
+        unsafe {
+            assert_eq!(mpfr::flags_save(), flags.bits());
+            let mut f = mem::zeroed();
+            mpfr::init2(&mut f, 53);
             {
-                use std::mem::MaybeUninit;
-                let mut maybe = MaybeUninit::uninit();
-                mpfr::init2(maybe.as_mut_ptr(), 4);
-                f = maybe.assume_init();
+                let guard = FlagsGuard::new();
+                assert_eq!(guard.saved(), flags);
+                Flags::ALL.clear();
+                mpfr::set_ui(&mut f, 0, mpfr::rnd_t::RNDN);
+                mpfr::ui_div(&mut f, 1, &f, mpfr::rnd_t::RNDN);
+                assert_eq!(Flags::current(), Flags::DIVBY0);
             }
-            #[cfg(not(maybe_uninit))]
+            assert_eq!(Flags::current(), flags);
+            mpfr::clear(&mut f);
+        }
+
+        let result = panic::catch_unwind(|| {
+            let _guard = FlagsGuard::new();
+            Flags::ALL.raise();
+            panic!("flags raised");
+        });
+        assert!(result.is_err());
+        assert_eq!(Flags::current(), flags);
+        Flags::ALL.clear();
+    }
+
+    #[test]
+    fn check_fixed_mpfr() {
+        use mpfr::FixedMpfr;
+
+        let prec = FixedMpfr::<3>::MAX_PREC;
+        unsafe {
+            let mut heap = mem::zeroed();
+            mpfr::init2(&mut heap, prec);
+            mpfr::const_pi(&mut heap, mpfr::rnd_t::RNDN);
+            mpfr::sqrt(&mut heap, &heap, mpfr::rnd_t::RNDN);
+
+            let mut fixed = FixedMpfr::<3>::new(prec);
+            assert_eq!(mpfr::nan_p(fixed.as_ptr()), 1);
+            mpfr::const_pi(fixed.as_mut_ptr(), mpfr::rnd_t::RNDN);
+            // move it around between operations
+            let mut moved = vec![FixedMpfr::<3>::new(1), fixed];
+            let mut fixed = moved.remove(1);
+            let fp = fixed.as_mut_ptr();
+            mpfr::sqrt(fp, fp, mpfr::rnd_t::RNDN);
+            let copy = fixed.clone();
+            fixed.set_prec(1);
+            assert_eq!(mpfr::get_prec(copy.as_ptr()), prec);
+            assert_eq!(mpfr::equal_p(copy.as_ptr(), &heap), 1);
+
+            let mut small = FixedMpfr::<3>::new(prec);
+            small.set_prec(10);
+            assert_eq!(small.prec(), 10);
+            assert_eq!(mpfr::nan_p(small.as_ptr()), 1);
+            mpfr::set(small.as_mut_ptr(), copy.as_ptr(), mpfr::rnd_t::RNDN);
+            mpfr::set_prec(&mut heap, 10);
+            mpfr::const_pi(&mut heap, mpfr::rnd_t::RNDN);
+            mpfr::sqrt(&mut heap, &heap, mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::equal_p(small.as_ptr(), &heap), 1);
+
+            // as_ptr resets the significand pointer after as_mut_ptr in
+            // the same call, and MPFR writes the result through it
+            let mut x = FixedMpfr::<1>::new(10);
+            mpfr::set_ui(x.as_mut_ptr(), 4, mpfr::rnd_t::RNDN);
+            mpfr::sqrt(x.as_mut_ptr(), x.as_ptr(), mpfr::rnd_t::RNDN);
+            assert_eq!(mpfr::cmp_ui(x.as_ptr(), 2), 0);
+
+            mpfr::clear(&mut heap);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "precision")]
+    fn check_fixed_mpfr_set_prec_too_large() {
+        let mut fixed = mpfr::FixedMpfr::<1>::new(1);
+        fixed.set_prec(mpfr::FixedMpfr::<1>::MAX_PREC + 1);
+    }
+
+    #[test]
+    fn check_exp_range_guard() {
+        use mpfr::ExpRangeGuard;
+        use std::panic;
+
+        unsafe {
+            let (emin, emax) = (mpfr::get_emin(), mpfr::get_emax());
+            assert!(ExpRangeGuard::new(10, -10).is_none());
+            assert!(ExpRangeGuard::new(mpfr::get_emin_min() - 1, 0).is_none());
+            assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (emin, emax));
             {
-                use std::mem;
-                f = mem::uninitialized();
-                mpfr::init2(&mut f, 4);
+                let _outer = ExpRangeGuard::new(-100, 100).unwrap();
+                {
+                    let _inner = ExpRangeGuard::new(-10, 10).unwrap();
+                    assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (-10, 10));
+                }
+                assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (-100, 100));
             }
+            assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (emin, emax));
 
-            // mpfr_round_nearest_away needs emin > emin_min
-            if mpfr::get_emin() == mpfr::get_emin_min() {
-                mpfr::set_emin(mpfr::get_emin_min() + 1);
+            let result = panic::catch_unwind(|| {
+                let _guard = ExpRangeGuard::new(-10, 10).unwrap();
+                panic!("range installed");
+            });
+            assert!(result.is_err());
+            assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (emin, emax));
+        }
+    }
+
+    type BinaryOp = unsafe extern "C" fn(
+        *mut mpfr::mpfr_t,
+        *const mpfr::mpfr_t,
+        *const mpfr::mpfr_t,
+        mpfr::rnd_t,
+    ) -> c_int;
+
+    unsafe fn ieee_op(format: mpfr::IeeeFormat, a: f64, b: f64, op: BinaryOp) -> f64 {
+        let guard = mpfr::ExpRangeGuard::ieee(format);
+        let mut fa = mem::zeroed();
+        mpfr::init2(&mut fa, format.prec());
+        let mut fb = mem::zeroed();
+        mpfr::init2(&mut fb, format.prec());
+        assert_eq!(mpfr::set_d(&mut fa, a, mpfr::rnd_t::RNDN), 0);
+        assert_eq!(mpfr::set_d(&mut fb, b, mpfr::rnd_t::RNDN), 0);
+        let fap: *mut mpfr::mpfr_t = &mut fa;
+        let dir = op(fap, fap, &fb, mpfr::rnd_t::RNDN);
+        guard.round(fap, dir, mpfr::rnd_t::RNDN);
+        let ret = mpfr::get_d(&fa, mpfr::rnd_t::RNDN);
+        mpfr::clear(&mut fb);
+        mpfr::clear(&mut fa);
+        ret
+    }
+
+    #[test]
+    fn check_ieee_emulation() {
+        use mpfr::IeeeFormat;
+
+        let f32_values = [
+            1.0f32,
+            -3.0,
+            0.1,
+            1e-3,
+            7e30,
+            f32::MAX,
+            f32::MIN_POSITIVE,
+            1.5e-45,
+            3.1e-42,
+            -2.5e-40,
+        ];
+        let f64_values = [
+            1.0f64,
+            -3.0,
+            0.1,
+            1e-3,
+            7e300,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            3.1e-310,
+            -2.5e-315,
+        ];
+        type Native = fn(f64, f64) -> f64;
+        let ops: [(BinaryOp, Native); 4] = [
+            (mpfr::add, |a, b| a + b),
+            (mpfr::sub, |a, b| a - b),
+            (mpfr::mul, |a, b| a * b),
+            (mpfr::div, |a, b| a / b),
+        ];
+        let same = |a: f64, b: f64| a == b && a.is_sign_negative() == b.is_sign_negative();
+        unsafe {
+            let (emin, emax) = (mpfr::get_emin(), mpfr::get_emax());
+            for &(op, native) in ops.iter() {
+                for &a in f32_values.iter() {
+                    for &b in f32_values.iter() {
+                        let (a, b) = (a as f64, b as f64);
+                        // f64 has more than twice the precision of f32, so
+                        // rounding the f64 result to f32 is correctly rounded
+                        let expected = native(a, b) as f32 as f64;
+                        let got = ieee_op(IeeeFormat::Binary32, a, b, op);
+                        assert!(same(got, expected), "{} {} {}", a, b, got);
+                    }
+                }
+                for &a in f64_values.iter() {
+                    for &b in f64_values.iter() {
+                        let expected = native(a, b);
+                        let got = ieee_op(IeeeFormat::Binary64, a, b, op);
+                        assert!(same(got, expected), "{} {} {}", a, b, got);
+                    }
+                }
             }
+            assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (emin, emax));
+        }
+    }
+
+    #[test]
+    fn check_round_nearest_away() {
+        unsafe {
+            let mut f = mpfr::new2(4);
+
+            // mpfr_round_nearest_away needs emin > emin_min
+            let _guard = mpfr::ExpRangeGuard::new(mpfr::get_emin_min() + 1, mpfr::get_emax());
 
             // tie to even: 10101 becomes 10100
             let dir_tie_even = mpfr::set_ui(&mut f, 21, mpfr::rnd_t::RNDN);
@@ -1506,4 +4144,70 @@ mod tests {
             mpfr::clear(&mut f);
         }
     }
+
+    #[test]
+    fn check_threads() {
+        use mpfr::{rnd_t, ExpRangeGuard, Flags};
+        use std::thread;
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<mpfr::mpfr_t>();
+
+        unsafe {
+            let prec = mpfr::get_default_prec();
+            let rnd = mpfr::get_default_rounding_mode();
+            let (emin, emax) = (mpfr::get_emin(), mpfr::get_emax());
+            let flags = Flags::current();
+
+            // changes to the thread-local state in another thread are
+            // not visible here
+            let mut f = thread::spawn(|| {
+                mpfr::set_default_prec(10);
+                mpfr::set_default_rounding_mode(mpfr::rnd_t::RNDZ);
+                let _guard = ExpRangeGuard::new(-20, 20).unwrap();
+                Flags::clear(Flags::ALL);
+                Flags::raise(Flags::DIVBY0);
+                let mut f = mem::zeroed();
+                mpfr::init(&mut f);
+                mpfr::const_pi(&mut f, mpfr::get_default_rounding_mode());
+                assert!(Flags::current().contains(Flags::DIVBY0 | Flags::INEXACT));
+                f
+            })
+            .join()
+            .unwrap();
+            assert_eq!(mpfr::get_default_prec(), prec);
+            assert_eq!(mpfr::get_default_rounding_mode(), rnd);
+            assert_eq!((mpfr::get_emin(), mpfr::get_emax()), (emin, emax));
+            assert_eq!(Flags::current(), flags);
+
+            // the value keeps its precision when moved
+            assert_eq!(mpfr::get_prec(&f), 10);
+            assert_eq!(mpfr::get_d(&f, rnd_t::RNDN), 3.140625);
+
+            // concurrent reads of the same value
+            thread::scope(|scope| {
+                let fr = &f;
+                for _ in 0..4 {
+                    scope.spawn(move || {
+                        assert_eq!(mpfr::get_d(fr, rnd_t::RNDN), 3.140625);
+                    });
+                }
+            });
+
+            // operations use the exponent range of the current thread
+            let mut f = thread::spawn(move || {
+                let _guard = ExpRangeGuard::new(-20, 20).unwrap();
+                Flags::clear(Flags::ALL);
+                let fp: *mut mpfr::mpfr_t = &mut f;
+                mpfr::mul_2ui(fp, fp, 20, rnd_t::RNDN);
+                assert_eq!(mpfr::inf_p(fp), 1);
+                assert!(Flags::current().contains(Flags::OVERFLOW));
+                f
+            })
+            .join()
+            .unwrap();
+            assert_eq!(mpfr::inf_p(&f), 1);
+            assert_eq!(Flags::current(), flags);
+            mpfr::clear(&mut f);
+        }
+    }
 }
@@ -0,0 +1,430 @@
+// Copyright © 2017–2019 University of Malta
+
+// This program is free software: you can redistribute it and/or
+// modify it under the terms of the GNU Lesser General Public License
+// as published by the Free Software Foundation, either version 3 of
+// the License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public
+// License and a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+
+/*!
+Thin owning wrappers that free the raw values when dropped.
+
+Each type in this module owns an initialized raw value, clears it when
+dropped, and clones it using the corresponding initialize-and-set
+function. The raw value is available for reading through [`Deref`]
+and `as_ptr`, and for writing only through `as_mut_ptr`, so that the
+wrappers can be passed to the bindings directly. No arithmetic is
+provided; this module is meant as a thin layer under higher-level
+crates.
+
+This module is only available if the `owned` feature is enabled.
+
+# Examples
+
+```rust
+use gmp_mpfr_sys::gmp;
+use gmp_mpfr_sys::owned::OwnedMpz;
+let mut a = OwnedMpz::new();
+unsafe {
+    gmp::mpz_set_ui(a.as_mut_ptr(), 10);
+}
+let mut b = a.clone();
+unsafe {
+    gmp::mpz_mul(b.as_mut_ptr(), a.as_ptr(), &*a);
+    assert_eq!(gmp::mpz_get_ui(&*b), 100);
+    assert_eq!(gmp::mpz_get_ui(a.as_ptr()), 10);
+}
+// both a and b are cleared when they go out of scope
+```
+
+[`Deref`]: https://doc.rust-lang.org/std/ops/trait.Deref.html
+*/
+
+use gmp;
+#[cfg(feature = "mpc")]
+use mpc;
+#[cfg(feature = "mpfr")]
+use mpfr;
+use std::mem::{self, MaybeUninit};
+use std::ops::Deref;
+
+macro_rules! owned_common {
+    ($Owned:ident, $raw:ty, $clear:path) => {
+        impl $Owned {
+            /// Takes ownership of an initialized raw value, which will
+            /// be cleared when the wrapper is dropped.
+            ///
+            /// # Safety
+            ///
+            /// `raw` must be initialized, and it must not be used or
+            /// cleared elsewhere afterwards.
+            #[inline]
+            pub unsafe fn from_raw(raw: $raw) -> $Owned {
+                $Owned { inner: raw }
+            }
+
+            /// Releases ownership of the raw value, which then has to
+            /// be cleared by the caller.
+            #[inline]
+            pub fn into_raw(self) -> $raw {
+                let raw = self.inner;
+                mem::forget(self);
+                raw
+            }
+
+            /// Returns a pointer to the raw value for reading.
+            #[inline]
+            pub fn as_ptr(&self) -> *const $raw {
+                &self.inner
+            }
+
+            /// Returns a pointer to the raw value for reading and
+            /// writing.
+            #[inline]
+            pub fn as_mut_ptr(&mut self) -> *mut $raw {
+                &mut self.inner
+            }
+        }
+
+        impl Deref for $Owned {
+            type Target = $raw;
+            #[inline]
+            fn deref(&self) -> &$raw {
+                &self.inner
+            }
+        }
+
+        impl Drop for $Owned {
+            #[inline]
+            fn drop(&mut self) {
+                unsafe {
+                    $clear(&mut self.inner);
+                }
+            }
+        }
+    };
+}
+
+/// An owned [`mpz_t`], cleared using [`mpz_clear`] when dropped.
+///
+/// [`mpz_clear`]: ../gmp/fn.mpz_clear.html
+/// [`mpz_t`]: ../gmp/struct.mpz_t.html
+#[derive(Debug)]
+pub struct OwnedMpz {
+    inner: gmp::mpz_t,
+}
+
+owned_common! { OwnedMpz, gmp::mpz_t, gmp::mpz_clear }
+
+impl OwnedMpz {
+    /// Creates an integer with a value of zero.
+    #[inline]
+    pub fn new() -> OwnedMpz {
+        OwnedMpz {
+            inner: gmp::mpz_new(),
+        }
+    }
+}
+
+impl Default for OwnedMpz {
+    #[inline]
+    fn default() -> OwnedMpz {
+        OwnedMpz::new()
+    }
+}
+
+impl Clone for OwnedMpz {
+    #[inline]
+    fn clone(&self) -> OwnedMpz {
+        let mut raw = MaybeUninit::uninit();
+        unsafe {
+            gmp::mpz_init_set(raw.as_mut_ptr(), &self.inner);
+            OwnedMpz::from_raw(raw.assume_init())
+        }
+    }
+}
+
+/// An owned [`mpq_t`], cleared using [`mpq_clear`] when dropped.
+///
+/// [`mpq_clear`]: ../gmp/fn.mpq_clear.html
+/// [`mpq_t`]: ../gmp/struct.mpq_t.html
+#[derive(Debug)]
+pub struct OwnedMpq {
+    inner: gmp::mpq_t,
+}
+
+owned_common! { OwnedMpq, gmp::mpq_t, gmp::mpq_clear }
+
+impl OwnedMpq {
+    /// Creates a rational number with a value of zero.
+    #[inline]
+    pub fn new() -> OwnedMpq {
+        OwnedMpq {
+            inner: gmp::mpq_new(),
+        }
+    }
+}
+
+impl Default for OwnedMpq {
+    #[inline]
+    fn default() -> OwnedMpq {
+        OwnedMpq::new()
+    }
+}
+
+impl Clone for OwnedMpq {
+    #[inline]
+    fn clone(&self) -> OwnedMpq {
+        let mut ret = OwnedMpq::new();
+        unsafe {
+            gmp::mpq_set(ret.as_mut_ptr(), &self.inner);
+        }
+        ret
+    }
+}
+
+/// An owned [`mpf_t`], cleared using [`mpf_clear`] when dropped.
+///
+/// [`mpf_clear`]: ../gmp/fn.mpf_clear.html
+/// [`mpf_t`]: ../gmp/struct.mpf_t.html
+#[derive(Debug)]
+pub struct OwnedMpf {
+    inner: gmp::mpf_t,
+}
+
+owned_common! { OwnedMpf, gmp::mpf_t, gmp::mpf_clear }
+
+impl OwnedMpf {
+    /// Creates a floating-point number with a precision of at least
+    /// `prec` bits and a value of zero.
+    #[inline]
+    pub fn new2(prec: gmp::bitcnt_t) -> OwnedMpf {
+        OwnedMpf {
+            inner: gmp::mpf_new2(prec),
+        }
+    }
+}
+
+impl Clone for OwnedMpf {
+    /// Clones the number, keeping its precision.
+    #[inline]
+    fn clone(&self) -> OwnedMpf {
+        unsafe {
+            let mut ret = OwnedMpf::new2(gmp::mpf_get_prec(&self.inner));
+            gmp::mpf_set(ret.as_mut_ptr(), &self.inner);
+            ret
+        }
+    }
+}
+
+/// An owned [`randstate_t`], cleared using [`randclear`] when dropped.
+///
+/// [`randclear`]: ../gmp/fn.randclear.html
+/// [`randstate_t`]: ../gmp/struct.randstate_t.html
+#[derive(Debug)]
+pub struct OwnedRandState {
+    inner: gmp::randstate_t,
+}
+
+owned_common! { OwnedRandState, gmp::randstate_t, gmp::randclear }
+
+impl OwnedRandState {
+    /// Creates a random state for the Mersenne Twister algorithm.
+    #[inline]
+    pub fn new_mt() -> OwnedRandState {
+        OwnedRandState {
+            inner: gmp::randstate_new_mt(),
+        }
+    }
+}
+
+impl Clone for OwnedRandState {
+    /// Clones the random state, so that the clone generates the same
+    /// sequence as the original.
+    #[inline]
+    fn clone(&self) -> OwnedRandState {
+        let mut raw = MaybeUninit::uninit();
+        unsafe {
+            gmp::randinit_set(raw.as_mut_ptr(), &self.inner);
+            OwnedRandState::from_raw(raw.assume_init())
+        }
+    }
+}
+
+/// An owned [`mpfr_t`], cleared using [`mpfr::clear`] when dropped.
+///
+/// This type is only available if the `mpfr` feature is enabled.
+///
+/// [`mpfr::clear`]: ../mpfr/fn.clear.html
+/// [`mpfr_t`]: ../mpfr/struct.mpfr_t.html
+#[cfg(feature = "mpfr")]
+#[derive(Debug)]
+pub struct OwnedMpfr {
+    inner: mpfr::mpfr_t,
+}
+
+#[cfg(feature = "mpfr")]
+owned_common! { OwnedMpfr, mpfr::mpfr_t, mpfr::clear }
+
+#[cfg(feature = "mpfr")]
+impl OwnedMpfr {
+    /// Creates a floating-point number with precision `prec` and a
+    /// value of NaN.
+    #[inline]
+    pub fn new2(prec: mpfr::prec_t) -> OwnedMpfr {
+        OwnedMpfr {
+            inner: mpfr::new2(prec),
+        }
+    }
+}
+
+#[cfg(feature = "mpfr")]
+impl Clone for OwnedMpfr {
+    /// Clones the number, keeping its precision.
+    #[inline]
+    fn clone(&self) -> OwnedMpfr {
+        unsafe {
+            let mut ret = OwnedMpfr::new2(mpfr::get_prec(&self.inner));
+            mpfr::set(ret.as_mut_ptr(), &self.inner, mpfr::rnd_t::RNDN);
+            ret
+        }
+    }
+}
+
+/// An owned [`mpc_t`], cleared using [`mpc::clear`] when dropped.
+///
+/// This type is only available if the `mpc` feature is enabled.
+///
+/// [`mpc::clear`]: ../mpc/fn.clear.html
+/// [`mpc_t`]: ../mpc/struct.mpc_t.html
+#[cfg(feature = "mpc")]
+#[derive(Debug)]
+pub struct OwnedMpc {
+    inner: mpc::mpc_t,
+}
+
+#[cfg(feature = "mpc")]
+owned_common! { OwnedMpc, mpc::mpc_t, mpc::clear }
+
+#[cfg(feature = "mpc")]
+impl OwnedMpc {
+    /// Creates a complex number with precisions `prec_r` and `prec_i`
+    /// for the real and imaginary parts, both of which are NaN.
+    #[inline]
+    pub fn new3(prec_r: mpfr::prec_t, prec_i: mpfr::prec_t) -> OwnedMpc {
+        OwnedMpc {
+            inner: mpc::new3(prec_r, prec_i),
+        }
+    }
+}
+
+#[cfg(feature = "mpc")]
+impl Clone for OwnedMpc {
+    /// Clones the number, keeping the precisions of both parts.
+    #[inline]
+    fn clone(&self) -> OwnedMpc {
+        unsafe {
+            let (mut prec_r, mut prec_i) = (0, 0);
+            mpc::get_prec2(&mut prec_r, &mut prec_i, &self.inner);
+            let mut ret = OwnedMpc::new3(prec_r, prec_i);
+            mpc::set(ret.as_mut_ptr(), &self.inner, mpc::RNDNN);
+            ret
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gmp;
+    #[cfg(feature = "mpc")]
+    use mpc;
+    #[cfg(feature = "mpfr")]
+    use mpfr;
+    use owned::*;
+
+    #[test]
+    fn check_gmp_types() {
+        unsafe {
+            let mut z = OwnedMpz::new();
+            gmp::mpz_set_si(z.as_mut_ptr(), -7);
+            let z2 = z.clone();
+            gmp::mpz_neg(z.as_mut_ptr(), &*z2);
+            assert_eq!(gmp::mpz_get_si(z.as_ptr()), 7);
+            assert_eq!(gmp::mpz_get_si(z2.as_ptr()), -7);
+            let mut raw = z.into_raw();
+            gmp::mpz_clear(&mut raw);
+
+            let mut q = OwnedMpq::default();
+            gmp::mpq_set_si(q.as_mut_ptr(), 3, 4);
+            let q2 = q.clone();
+            gmp::mpq_set_ui(q.as_mut_ptr(), 0, 1);
+            assert_eq!(gmp::mpq_cmp_si(q2.as_ptr(), 3, 4), 0);
+
+            let mut f = OwnedMpf::new2(500);
+            gmp::mpf_set_d(f.as_mut_ptr(), 1.5);
+            let f2 = f.clone();
+            assert_eq!(gmp::mpf_get_prec(&*f2), gmp::mpf_get_prec(&*f));
+            assert_eq!(gmp::mpf_cmp(&*f2, &*f), 0);
+
+            let mut state = OwnedRandState::new_mt();
+            gmp::randseed_ui(state.as_mut_ptr(), 7);
+            let mut state2 = state.clone();
+            for _ in 0..10 {
+                let a = gmp::urandomb_ui(state.as_mut_ptr(), 32);
+                let b = gmp::urandomb_ui(state2.as_mut_ptr(), 32);
+                assert_eq!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn check_threads() {
+        use std::thread;
+        unsafe {
+            let mut z = OwnedMpz::new();
+            gmp::mpz_set_ui(z.as_mut_ptr(), 5);
+            let state = OwnedRandState::new_mt();
+            let (z, _state) = thread::spawn(move || {
+                gmp::mpz_mul_ui(z.as_mut_ptr(), z.as_ptr(), 3);
+                (z.clone(), state)
+            })
+            .join()
+            .unwrap();
+            assert_eq!(gmp::mpz_get_ui(z.as_ptr()), 15);
+        }
+    }
+
+    #[cfg(feature = "mpfr")]
+    #[test]
+    fn check_mpfr() {
+        unsafe {
+            let mut f = OwnedMpfr::new2(100);
+            mpfr::const_pi(f.as_mut_ptr(), mpfr::rnd_t::RNDN);
+            let f2 = f.clone();
+            assert_eq!(mpfr::get_prec(f2.as_ptr()), 100);
+            assert_eq!(mpfr::equal_p(f2.as_ptr(), f.as_ptr()), 1);
+        }
+    }
+
+    #[cfg(feature = "mpc")]
+    #[test]
+    fn check_mpc() {
+        unsafe {
+            let mut c = OwnedMpc::new3(30, 60);
+            mpc::set_si_si(c.as_mut_ptr(), 1, -1, mpc::RNDNN);
+            let c2 = c.clone();
+            let (mut prec_r, mut prec_i) = (0, 0);
+            mpc::get_prec2(&mut prec_r, &mut prec_i, c2.as_ptr());
+            assert_eq!((prec_r, prec_i), (30, 60));
+            assert_eq!(mpc::cmp(c2.as_ptr(), c.as_ptr()), 0);
+        }
+    }
+}
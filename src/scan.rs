@@ -0,0 +1,485 @@
+// Copyright © 2017–2019 University of Malta
+
+// This program is free software: you can redistribute it and/or
+// modify it under the terms of the GNU Lesser General Public License
+// as published by the Free Software Foundation, either version 3 of
+// the License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful, but
+// WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU
+// General Public License for more details.
+//
+// You should have received a copy of the GNU Lesser General Public
+// License and a copy of the GNU General Public License along with
+// this program. If not, see <https://www.gnu.org/licenses/>.
+
+/*!
+Type-checked formatted input.
+
+The [`gmp_sscanf!`] and [`gmp_fscanf!`] macros parse GMP numbers using
+the conversion specifications of [`gmp::sscanf`] and [`gmp::fscanf`],
+but unlike those variadic functions, they check the conversion
+specifications against the types of the destinations when the crate
+is compiled.
+
+The supported conversion specifications are:
+
+| Specification | Destination    | Conversions   |
+|---------------|----------------|---------------|
+| `%Z`          | [`gmp::mpz_t`] | `d i o u x X` |
+| `%Q`          | [`gmp::mpq_t`] | `d i o u x X` |
+| `%F`          | [`gmp::mpf_t`] | `e E f g G`   |
+
+Each specification can have the flag `*` to read a value without
+storing it, in which case it takes no destination, and a maximum field
+width, for example `%*Zd` or `%10Zx`. A literal `%` is written as
+`%%`. Whitespace and other characters in the format string match the
+input as for the C `scanf` functions.
+
+A destination can be a mutable reference or a mutable raw pointer to
+the number, so both `&mut z` and `&mut z as *mut gmp::mpz_t` are
+accepted for a `%Zd` specification.
+
+As with [`gmp::sscanf`], values read using `%Q` are not canonicalized;
+if the value might not be in canonical form, [`gmp::mpq_canonicalize`]
+must be called before using it in calculations.
+
+# Examples
+
+```rust
+use gmp_mpfr_sys::gmp;
+use std::mem;
+unsafe {
+    let mut z = mem::zeroed();
+    gmp::mpz_init(&mut z);
+    let mut q = mem::zeroed();
+    gmp::mpq_init(&mut q);
+    let n = gmp_mpfr_sys::gmp_sscanf!("a(5) = -12, b = 3/4", "a(5) = %Zd, b = %Qd", &mut z, &mut q);
+    assert_eq!(n, Ok(2));
+    assert_eq!(gmp::mpz_get_si(&z), -12);
+    assert_eq!(gmp::mpq_get_d(&q), 0.75);
+    gmp::mpq_clear(&mut q);
+    gmp::mpz_clear(&mut z);
+}
+```
+
+[`gmp::fscanf`]: ../gmp/fn.fscanf.html
+[`gmp::mpf_t`]: ../gmp/struct.mpf_t.html
+[`gmp::mpq_canonicalize`]: ../gmp/fn.mpq_canonicalize.html
+[`gmp::mpq_t`]: ../gmp/struct.mpq_t.html
+[`gmp::mpz_t`]: ../gmp/struct.mpz_t.html
+[`gmp::sscanf`]: ../gmp/fn.sscanf.html
+[`gmp_fscanf!`]: ../macro.gmp_fscanf.html
+[`gmp_sscanf!`]: ../macro.gmp_sscanf.html
+*/
+
+use format::Template;
+use gmp;
+use libc::FILE;
+use std::error::Error;
+use std::ffi::CString;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_int, c_void};
+
+/// Parses GMP numbers from a string, checking the conversion
+/// specifications against the destination types at compile time.
+///
+/// The first argument is the input string, which must be a `&str`.
+/// The second argument is the format string, which must be a constant
+/// expression of type `&'static str`, such as a string literal. The
+/// supported conversion specifications are listed in the [`scan`]
+/// module.
+///
+/// The macro returns `Ok` with the number of items stored if all the
+/// destinations were stored, otherwise it returns a [`ScanError`].
+///
+/// The macro must be used inside an `unsafe` block, as all the
+/// destinations must point to initialized numbers.
+///
+/// # Examples
+///
+/// ```rust
+/// use gmp_mpfr_sys::gmp;
+/// use gmp_mpfr_sys::scan::ScanError;
+/// use std::mem;
+/// unsafe {
+///     let mut f = mem::zeroed();
+///     gmp::mpf_init(&mut f);
+///     let n = gmp_mpfr_sys::gmp_sscanf!("x=1.5e3", "x=%Ff", &mut f);
+///     assert_eq!(n, Ok(1));
+///     assert_eq!(gmp::mpf_get_d(&f), 1500.0);
+///     let n = gmp_mpfr_sys::gmp_sscanf!("x=", "x=%Ff", &mut f);
+///     assert_eq!(n, Err(ScanError::Eof));
+///     gmp::mpf_clear(&mut f);
+/// }
+/// ```
+///
+/// Mismatched destinations are a compile-time error.
+///
+/// ```rust,compile_fail
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut q: gmp::mpq_t = mem::zeroed();
+///     gmp::mpq_init(&mut q);
+///     // %Zd expects an mpz_t, not an mpq_t
+///     let _ = gmp_mpfr_sys::gmp_sscanf!("12", "%Zd", &mut q);
+///     gmp::mpq_clear(&mut q);
+/// }
+/// ```
+///
+/// [`ScanError`]: scan/enum.ScanError.html
+/// [`scan`]: scan/index.html
+#[macro_export]
+macro_rules! gmp_sscanf {
+    ($input:expr, $fmt:expr $(, $dest:expr)* $(,)?) => {{
+        struct Template;
+        impl $crate::format::Template for Template {
+            const FMT: &'static str = $fmt;
+        }
+        $crate::scan::sscanf::<Template, _>($input, ($($dest,)*))
+    }};
+}
+
+/// Parses GMP numbers from a C `FILE` stream, checking the conversion
+/// specifications against the destination types at compile time.
+///
+/// This is like [`gmp_sscanf!`], but the first argument is a
+/// `*mut FILE` instead of a string. On GNU/Linux, the [`file`] module
+/// can be used to read from a Rust reader.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[cfg(target_os = "linux")] {
+/// use gmp_mpfr_sys::file;
+/// use gmp_mpfr_sys::gmp;
+/// use std::mem;
+/// unsafe {
+///     let mut z = mem::zeroed();
+///     gmp::mpz_init(&mut z);
+///     let mut input: &[u8] = b"0x1f";
+///     let n = file::with_reader(&mut input, |fp| gmp_mpfr_sys::gmp_fscanf!(fp, "%Zi", &mut z));
+///     assert_eq!(n.unwrap(), Ok(1));
+///     assert_eq!(gmp::mpz_get_si(&z), 31);
+///     gmp::mpz_clear(&mut z);
+/// }
+/// # }
+/// ```
+///
+/// [`file`]: file/index.html
+/// [`gmp_sscanf!`]: macro.gmp_sscanf.html
+#[macro_export]
+macro_rules! gmp_fscanf {
+    ($fp:expr, $fmt:expr $(, $dest:expr)* $(,)?) => {{
+        struct Template;
+        impl $crate::format::Template for Template {
+            const FMT: &'static str = $fmt;
+        }
+        $crate::scan::fscanf::<Template, _>($fp, ($($dest,)*))
+    }};
+}
+
+/// An error returned by [`gmp_sscanf!`] or [`gmp_fscanf!`].
+///
+/// [`gmp_fscanf!`]: ../macro.gmp_fscanf.html
+/// [`gmp_sscanf!`]: ../macro.gmp_sscanf.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ScanError {
+    /// The input string contains a nul character.
+    NulInInput,
+    /// The input ended, or a read error occurred, before the first
+    /// conversion.
+    Eof,
+    /// The input did not match the format string, so only `stored`
+    /// of the `expected` destinations were stored.
+    Mismatch {
+        /// The number of destinations that were stored.
+        stored: usize,
+        /// The number of destinations.
+        expected: usize,
+    },
+}
+
+impl Display for ScanError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            ScanError::NulInInput => f.write_str("nul character found in input"),
+            ScanError::Eof => f.write_str("end of input before first conversion"),
+            ScanError::Mismatch { stored, expected } => write!(
+                f,
+                "input does not match format, {} of {} items stored",
+                stored, expected
+            ),
+        }
+    }
+}
+
+impl Error for ScanError {}
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub enum Kind {
+    Z,
+    Q,
+    F,
+}
+
+/// A destination for a conversion specification of [`gmp_sscanf!`] or
+/// [`gmp_fscanf!`].
+///
+/// This trait is implemented for mutable references and mutable raw
+/// pointers to [`gmp::mpz_t`], [`gmp::mpq_t`] and [`gmp::mpf_t`].
+///
+/// [`gmp::mpf_t`]: ../gmp/struct.mpf_t.html
+/// [`gmp::mpq_t`]: ../gmp/struct.mpq_t.html
+/// [`gmp::mpz_t`]: ../gmp/struct.mpz_t.html
+/// [`gmp_fscanf!`]: ../macro.gmp_fscanf.html
+/// [`gmp_sscanf!`]: ../macro.gmp_sscanf.html
+pub trait Dest {
+    #[doc(hidden)]
+    const KIND: Kind;
+    #[doc(hidden)]
+    fn into_void_ptr(self) -> *mut c_void;
+}
+
+macro_rules! dest {
+    ($T:ty, $kind:ident) => {
+        impl<'a> Dest for &'a mut $T {
+            const KIND: Kind = Kind::$kind;
+            fn into_void_ptr(self) -> *mut c_void {
+                self as *mut $T as *mut c_void
+            }
+        }
+
+        impl Dest for *mut $T {
+            const KIND: Kind = Kind::$kind;
+            fn into_void_ptr(self) -> *mut c_void {
+                self as *mut c_void
+            }
+        }
+    };
+}
+
+dest! { gmp::mpz_t, Z }
+dest! { gmp::mpq_t, Q }
+dest! { gmp::mpf_t, F }
+
+#[doc(hidden)]
+pub trait DestList {
+    const KINDS: &'static [Kind];
+    unsafe fn sscanf(self, s: *const c_char, fmt: *const c_char) -> c_int;
+    unsafe fn fscanf(self, fp: *mut FILE, fmt: *const c_char) -> c_int;
+}
+
+macro_rules! dest_list {
+    ($($T:ident $i:tt),*) => {
+        impl<$($T: Dest),*> DestList for ($($T,)*) {
+            const KINDS: &'static [Kind] = &[$($T::KIND),*];
+            #[allow(unused_variables)]
+            unsafe fn sscanf(self, s: *const c_char, fmt: *const c_char) -> c_int {
+                gmp::sscanf(s, fmt $(, self.$i.into_void_ptr())*)
+            }
+            #[allow(unused_variables)]
+            unsafe fn fscanf(self, fp: *mut FILE, fmt: *const c_char) -> c_int {
+                gmp::fscanf(fp, fmt $(, self.$i.into_void_ptr())*)
+            }
+        }
+    };
+}
+
+dest_list! {}
+dest_list! { A 0 }
+dest_list! { A 0, B 1 }
+dest_list! { A 0, B 1, C 2 }
+dest_list! { A 0, B 1, C 2, D 3 }
+dest_list! { A 0, B 1, C 2, D 3, E 4 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10 }
+dest_list! { A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11 }
+
+// Checks the format string against the destination kinds and returns
+// the number of destinations.
+const fn validate(fmt: &str, kinds: &[Kind]) -> usize {
+    let fmt = fmt.as_bytes();
+    let len = fmt.len();
+    let mut n = 0;
+    let mut i = 0;
+    while i < len {
+        if fmt[i] == 0 {
+            panic!("format string contains a nul byte");
+        }
+        if fmt[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        i += 1;
+        if i < len && fmt[i] == b'%' {
+            i += 1;
+            continue;
+        }
+        let suppress = i < len && fmt[i] == b'*';
+        if suppress {
+            i += 1;
+        }
+        while i < len && fmt[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == len {
+            panic!("incomplete conversion specification");
+        }
+        let kind = match fmt[i] {
+            b'Z' => Kind::Z,
+            b'Q' => Kind::Q,
+            b'F' => Kind::F,
+            _ => panic!("unsupported conversion specification, expected %Z, %Q or %F"),
+        };
+        i += 1;
+        if i == len {
+            panic!("incomplete conversion specification");
+        }
+        let valid = match kind {
+            Kind::Z | Kind::Q => matches!(fmt[i], b'd' | b'i' | b'o' | b'u' | b'x' | b'X'),
+            Kind::F => matches!(fmt[i], b'e' | b'E' | b'f' | b'g' | b'G'),
+        };
+        if !valid {
+            panic!("invalid conversion for the destination type");
+        }
+        i += 1;
+        if suppress {
+            continue;
+        }
+        if n == kinds.len() {
+            panic!("too few destinations for format string");
+        }
+        if kinds[n] as u8 != kind as u8 {
+            panic!("destination type does not match conversion specification");
+        }
+        n += 1;
+    }
+    if n != kinds.len() {
+        panic!("too many destinations for format string");
+    }
+    n
+}
+
+// Evaluating EXPECTED is a compile-time error if the destinations do
+// not match the template.
+struct Check<T, D>(PhantomData<(T, D)>);
+
+impl<T: Template, D: DestList> Check<T, D> {
+    const EXPECTED: usize = validate(T::FMT, D::KINDS);
+}
+
+fn result(ret: c_int, expected: usize) -> Result<usize, ScanError> {
+    if ret < 0 {
+        Err(ScanError::Eof)
+    } else if ret as usize == expected {
+        Ok(expected)
+    } else {
+        Err(ScanError::Mismatch {
+            stored: ret as usize,
+            expected,
+        })
+    }
+}
+
+#[doc(hidden)]
+pub unsafe fn sscanf<T: Template, D: DestList>(input: &str, dests: D) -> Result<usize, ScanError> {
+    let expected = Check::<T, D>::EXPECTED;
+    let input = match CString::new(input) {
+        Ok(input) => input,
+        Err(_) => return Err(ScanError::NulInInput),
+    };
+    let fmt = CString::new(T::FMT).expect("nul byte in format string");
+    result(dests.sscanf(input.as_ptr(), fmt.as_ptr()), expected)
+}
+
+#[doc(hidden)]
+pub unsafe fn fscanf<T: Template, D: DestList>(
+    fp: *mut FILE,
+    dests: D,
+) -> Result<usize, ScanError> {
+    let expected = Check::<T, D>::EXPECTED;
+    let fmt = CString::new(T::FMT).expect("nul byte in format string");
+    result(dests.fscanf(fp, fmt.as_ptr()), expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use gmp;
+    use scan::ScanError;
+    use std::mem;
+
+    #[test]
+    fn check_gmp_types() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            let mut q = mem::zeroed();
+            gmp::mpq_init(&mut q);
+            let mut f = mem::zeroed();
+            gmp::mpf_init(&mut f);
+            let fp = &mut f as *mut gmp::mpf_t;
+
+            let n = gmp_sscanf!("0x1F 7/-14 -2.5e-1", "%Zi %Qd %Ff", &mut z, &mut q, fp);
+            assert_eq!(n, Ok(3));
+            assert_eq!(gmp::mpz_get_si(&z), 31);
+            gmp::mpq_canonicalize(&mut q);
+            assert_eq!(gmp::mpq_get_d(&q), -0.5);
+            assert_eq!(gmp::mpf_get_d(&f), -0.25);
+
+            let n = gmp_sscanf!("7 100% 12345", "%*Zd %3Zd%%%*1Zd%2Qd", &mut z, &mut q);
+            assert_eq!(n, Ok(2));
+            assert_eq!(gmp::mpz_get_si(&z), 100);
+            assert_eq!(gmp::mpq_get_d(&q), 23.0);
+
+            assert_eq!(gmp_sscanf!("", ""), Ok(0));
+
+            gmp::mpf_clear(&mut f);
+            gmp::mpq_clear(&mut q);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+
+    #[test]
+    fn check_malformed() {
+        unsafe {
+            let mut z = mem::zeroed();
+            gmp::mpz_init(&mut z);
+            let mut q = mem::zeroed();
+            gmp::mpq_init(&mut q);
+
+            let n = gmp_sscanf!("12 abc", "%Zd %Qd", &mut z, &mut q);
+            assert_eq!(
+                n,
+                Err(ScanError::Mismatch {
+                    stored: 1,
+                    expected: 2
+                })
+            );
+            assert_eq!(gmp::mpz_get_si(&z), 12);
+            let n = gmp_sscanf!("x", "%Zd", &mut z);
+            assert_eq!(
+                n,
+                Err(ScanError::Mismatch {
+                    stored: 0,
+                    expected: 1
+                })
+            );
+            let n = gmp_sscanf!("   ", "%Qd", &mut q);
+            assert_eq!(n, Err(ScanError::Eof));
+            let n = gmp_sscanf!("1\0 2", "%Zd", &mut z);
+            assert_eq!(n, Err(ScanError::NulInInput));
+
+            gmp::mpq_clear(&mut q);
+            gmp::mpz_clear(&mut z);
+        }
+    }
+}